@@ -1,47 +1,332 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
-use chrono::{Local, NaiveDate, TimeDelta};
+use chrono::{Local, NaiveDate, NaiveDateTime, TimeDelta};
 use indexmap::IndexMap;
-use indicium::simple::{SearchIndex, SearchIndexBuilder};
+use indicium::simple::{Indexable, SearchIndex, SearchIndexBuilder};
 use leptos::{
-    create_rw_signal, expect_context, provide_context, with_current_owner, Callback, MaybeSignal,
-    RwSignal, Signal, SignalGet, SignalUpdate, SignalWith, SignalWithUntracked,
+    create_effect, create_memo, create_rw_signal, expect_context, provide_context,
+    with_current_owner, Callback, MaybeSignal, Memo, RwSignal, Signal, SignalGet, SignalUpdate,
+    SignalWith, SignalWithUntracked,
 };
 
-use crate::types::{
-    HasBeginning, Identifier, Identify, LevelUp, MainCategory, MyName, NonSignalPointOfInterest,
-    Person, PointInTime, PointOfInterest, Timeline,
+use crate::{
+    format::Style,
+    parse::ParseContext,
+    solver::{relations, solve, Solution},
+    types::{
+        Calibration, Certainty, Claim, Coordinates, Duration, Ending, EntityKind, HasBeginning,
+        Identifier, Identify, Interval, LevelUp, Lookup, MainCategory, MyName, NamedEntity,
+        NonSignalPointOfInterest, Person, Place, PointInTime, PointOfInterest, Recurrence, Season,
+        SeasonModel, Sequence, Source, SourceKind, Stadium, Tag, Term, TimeError, Timeline,
+    },
+    validation::{check, Warning},
 };
 
 #[derive(Clone)]
 pub struct TimelineContext {
     pub pois: RwSignal<PoIs>,
+    pub chronology: Chronology,
+    pub solution: Memo<Solution>,
+    /// For PoIs left without an end, the PoI that is taken to end them
+    pub inferred_ends: Memo<HashMap<Identifier, Identifier>>,
+    pub warnings: Memo<Vec<Warning>>,
+    /// How dates are written out
+    pub style: RwSignal<Style>,
+    /// PoIs with dates less certain than this are left out of the timeline
+    pub least_certain: RwSignal<Option<Certainty>>,
+    /// PoIs not tagged with this, or anything nested under it, are dimmed or left out
+    pub tag_filter: RwSignal<Option<TagFilter>>,
     pub cats: RwSignal<IndexMap<Identifier, ByMainCategory>>,
-    pub add_poi: Callback<NonSignalPointOfInterest>,
+    /// Fills the schooling category with what the timeline's person can be expected
+    /// to have gone through
+    pub add_expected_schooling: Callback<()>,
     pub span: Signal<TimeDelta>,
     pub begins: Signal<Option<NaiveDate>>,
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagFilter {
+    pub tag: Tag,
+    /// Leave out the PoIs that do not match, instead of dimming them
+    pub hide: bool,
+}
+
+impl TagFilter {
+    pub fn lets_through(&self, poi: &PointOfInterest) -> bool {
+        poi.is_tagged(&self.tag)
+    }
+}
+
+/// Everything a point in time may refer to. Resolving through it tracks the
+/// underlying signals, so resolved dates follow later edits of what they refer to.
+#[derive(Debug, Clone, Copy)]
+pub struct Chronology {
+    pub pois: RwSignal<PoIs>,
+    pub people: RwSignal<HashMap<Identifier, Person>>,
+    pub sources: RwSignal<HashMap<Identifier, Source>>,
+    pub entities: RwSignal<Entities>,
+    pub places: RwSignal<HashMap<Identifier, Place>>,
+    pub timeline: RwSignal<Timeline>,
+    /// Shows the timeline as it was recorded by then, instead of as it is now
+    pub as_of: RwSignal<Option<NaiveDateTime>>,
+}
+
+impl Lookup for Chronology {
+    fn person(&self, id: &Identifier) -> Option<Person> {
+        self.people.with(|p| p.get(id).cloned())
+    }
+
+    fn poi(&self, id: &Identifier) -> Option<PointOfInterest> {
+        self.pois.with(|p| p.get(id).copied())
+    }
+
+    fn origo(&self) -> Option<Interval> {
+        let owner = self.timeline.with(|t| t.parent());
+        self.person(&owner).and_then(|p| p.born().ok())
+    }
+
+    fn source(&self, id: &Identifier) -> Option<Source> {
+        self.sources.with(|s| s.get(id).cloned())
+    }
+
+    fn entity(&self, id: &Identifier) -> Option<NamedEntity> {
+        self.entities.with(|e| e.get(id).cloned())
+    }
+
+    fn place(&self, id: &Identifier) -> Option<Place> {
+        self.places.with(|p| p.get(id).cloned())
+    }
+
+    fn as_of(&self) -> Option<NaiveDateTime> {
+        self.as_of.get()
+    }
+
+    fn seasons(&self) -> SeasonModel {
+        self.timeline.with(|t| t.seasons())
+    }
+}
+
+impl Chronology {
+    /// Every PoI that had been recorded by the time the timeline is seen as of
+    fn all_pois(&self) -> Vec<PointOfInterest> {
+        let as_of = self.as_of();
+        self.pois.with(|p| {
+            p.pois
+                .values()
+                .filter(|p| p.is_recorded_by(as_of))
+                .copied()
+                .collect::<Vec<_>>()
+        })
+    }
+
+    /// Works out the whole timeline at once, narrowing PoIs dated relative to
+    /// each other and finding those that contradict each other
+    pub fn solve(&self) -> Solution {
+        solve(&relations(&self.all_pois(), self), &self.seasons())
+    }
+
+    /// Orders the given PoIs by when they start, imprecise ones by their earliest
+    /// possible date. Those that cannot be placed in time end up last, and those
+    /// not yet recorded as of when the timeline is seen are left out.
+    pub fn chronological(&self, ids: impl IntoIterator<Item = Identifier>) -> Vec<Identifier> {
+        let as_of = self.as_of();
+        let mut ids = ids
+            .into_iter()
+            .filter(|id| self.poi(id).is_none_or(|p| p.is_recorded_by(as_of)))
+            .map(|id| (self.poi(&id).and_then(|p| p.starts_within(self).ok()), id))
+            .collect::<Vec<_>>();
+        ids.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => a.cmp(b),
+            (a, b) => b.is_some().cmp(&a.is_some()),
+        });
+        ids.into_iter().map(|(_, id)| id).collect()
+    }
+
+    /// What each PoI can be searched by, with its dates resolved through the
+    /// chronology
+    pub fn keywords(&self) -> HashMap<Identifier, Vec<String>> {
+        self.pois
+            .with(|p| p.pois.values().copied().collect::<Vec<_>>())
+            .into_iter()
+            .map(|p| (p.identity(), p.keywords(self)))
+            .collect()
+    }
+
+    /// Indexes again the PoIs whose keywords are not what they were last indexed
+    /// by, e.g. since what they are dated relative to was moved. Leaves the PoIs
+    /// alone when nothing is stale.
+    pub fn reindex(&self, keywords: &HashMap<Identifier, Vec<String>>) {
+        let stale = self.pois.with_untracked(|p| {
+            keywords
+                .iter()
+                .filter(|(id, k)| !p.is_indexed_by(id, k))
+                .map(|(id, k)| (*id, k.clone()))
+                .collect::<Vec<_>>()
+        });
+        if !stale.is_empty() {
+            self.pois.update(|p| {
+                for (id, keywords) in stale {
+                    p.index(id, keywords);
+                }
+            });
+        }
+    }
+
+    /// The PoIs traced back to a source, in the order they happened
+    pub fn cited_by(&self, source: &Identifier) -> Vec<Identifier> {
+        let citing = self
+            .all_pois()
+            .into_iter()
+            .filter(|p| p.cites(source))
+            .map(|p| p.identity());
+        self.chronological(citing)
+    }
+
+    /// The PoIs that mention an entity, in the order they happened
+    pub fn mentioning(&self, entity: &Identifier) -> Vec<Identifier> {
+        let mentioning = self
+            .all_pois()
+            .into_iter()
+            .filter(|p| p.mentions(entity))
+            .map(|p| p.identity());
+        self.chronological(mentioning)
+    }
+
+    /// How many times the PoIs of a category, such as places lived, change place,
+    /// and how long is spent in each place. Each stay lasts until it ends, or else
+    /// until the next one starts, or else until the day the timeline is seen as of.
+    pub fn residences(&self, ids: impl IntoIterator<Item = Identifier>) -> Residences {
+        let placed = self
+            .chronological(ids)
+            .into_iter()
+            .filter_map(|id| self.poi(&id))
+            .filter_map(|p| Some((p, p.place()?, p.starts_within(self).ok()?)))
+            .collect::<Vec<_>>();
+        let today = self.today();
+        let mut residences = Residences::default();
+        for (i, (poi, place, starts)) in placed.iter().enumerate() {
+            let next = placed.get(i + 1);
+            let ends = poi
+                .ends_within(self)
+                .ok()
+                .or_else(|| next.map(|(_, _, n)| *n))
+                .map_or(today, |e| e.earliest());
+            let stayed = (ends - starts.earliest()).max(TimeDelta::zero());
+            *residences.stays.entry(*place).or_insert(TimeDelta::zero()) += stayed;
+            if next.is_some_and(|(_, p, _)| p != place) {
+                residences.moves += 1;
+            }
+        }
+        residences
+    }
+
+    /// Every tag in use, along with each level it is nested under
+    pub fn tags(&self) -> BTreeSet<Tag> {
+        let mut tags = BTreeSet::new();
+        for mut tag in self.all_pois().iter().flat_map(|p| p.tags()) {
+            while let Some(parent) = tag.parent() {
+                tags.insert(tag);
+                tag = parent;
+            }
+            tags.insert(tag);
+        }
+        tags
+    }
+
+    /// The days anything about the timeline was recorded, e.g. one per meeting
+    pub fn sessions(&self) -> Vec<NaiveDate> {
+        let mut days = self.pois.with(|p| {
+            p.pois
+                .values()
+                .flat_map(|p| p.history())
+                .map(|r| r.recorded.date())
+                .collect::<Vec<_>>()
+        });
+        days.sort();
+        days.dedup();
+        days
+    }
+
+    /// What dates typed in for this timeline refer to
+    pub fn parse_context(&self) -> ParseContext {
+        ParseContext {
+            about: self.timeline.with(|t| t.parent()),
+            today: self.today(),
+        }
+    }
+}
+
+impl HasBeginning for Chronology {
+    /// When the earliest PoI that can be placed in time began
+    fn begins(&self) -> Result<NaiveDate, TimeError> {
+        self.all_pois()
+            .iter()
+            .filter_map(|p| p.starts_within(self).ok())
+            .map(|i| i.earliest())
+            .min()
+            .ok_or(TimeError::Undetermined)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PoIs {
     pois: HashMap<Identifier, PointOfInterest>,
     search: SearchIndex<Identifier>,
+    /// What each PoI was last indexed by, to take it out of the index again
+    keywords: HashMap<Identifier, Keywords>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Keywords(Vec<String>);
+
+impl Indexable for Keywords {
+    fn strings(&self) -> Vec<String> {
+        self.0.clone()
+    }
+}
+
+fn search_index() -> SearchIndex<Identifier> {
+    SearchIndexBuilder::default()
+        .autocomplete_type(indicium::simple::AutocompleteType::Context)
+        .exclude_keywords(None)
+        .build()
 }
 
 impl PoIs {
+    /// Indexes the PoIs by what they say on their own. Dates that depend on other
+    /// things are filled in by [`Chronology::reindex`].
     fn from_poi_collection(pois: HashMap<Identifier, PointOfInterest>) -> Self {
-        let mut search = SearchIndexBuilder::default()
-            .autocomplete_type(indicium::simple::AutocompleteType::Context)
-            .exclude_keywords(None)
-            .build();
-        pois.iter().for_each(|(k, v)| search.insert(k, v));
-        Self { pois, search }
+        let search = search_index();
+        let mut all = Self {
+            pois: HashMap::new(),
+            search,
+            keywords: HashMap::new(),
+        };
+        for (id, poi) in pois {
+            all.index(id, poi.keywords(&()));
+            all.pois.insert(id, poi);
+        }
+        all
     }
 
     fn insert(&mut self, poi: PointOfInterest) {
-        self.search.insert(&poi.identity(), &poi);
+        self.index(poi.identity(), poi.keywords(&()));
         self.pois.insert(poi.identity(), poi);
     }
+
+    fn index(&mut self, id: Identifier, keywords: Vec<String>) {
+        let keywords = Keywords(keywords);
+        if let Some(before) = self.keywords.remove(&id) {
+            self.search.remove(&id, &before);
+        }
+        self.search.insert(&id, &keywords);
+        self.keywords.insert(id, keywords);
+    }
+
+    fn is_indexed_by(&self, id: &Identifier, keywords: &[String]) -> bool {
+        self.keywords.get(id).is_some_and(|k| k.0 == keywords)
+    }
+
     pub fn keys(&self) -> std::iter::Copied<std::collections::hash_map::Keys<'_, Identifier, PointOfInterest>> {
         self.pois.keys().copied()
     }
@@ -56,13 +341,47 @@ impl PoIs {
     }
 }
 
-impl HasBeginning for PoIs {
-    fn begins(&self) -> NaiveDate {
-        self.pois.values().map(|o| o.begins()).min().unwrap()
+/// Where someone lived, counted over a category of PoIs
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Residences {
+    pub moves: usize,
+    /// Each place with the time spent there in all, in the order first lived in
+    pub stays: IndexMap<Identifier, TimeDelta>,
+}
+
+/// Everyone and everything the PoIs mention, searchable by name
+#[derive(Debug, Clone)]
+pub struct Entities {
+    entities: HashMap<Identifier, NamedEntity>,
+    search: SearchIndex<Identifier>,
+}
+
+impl Entities {
+    fn from_entities(entities: impl IntoIterator<Item = NamedEntity>) -> Self {
+        let mut registry = Self {
+            entities: HashMap::new(),
+            search: search_index(),
+        };
+        entities.into_iter().for_each(|e| registry.insert(e));
+        registry
     }
 
-    fn try_begins(&self) -> Option<NaiveDate> {
-        self.pois.values().map(|o| o.begins()).min()
+    pub fn insert(&mut self, entity: NamedEntity) {
+        let id = entity.identity();
+        if let Some(before) = self.entities.get(&id) {
+            self.search.remove(&id, before);
+        }
+        self.search.insert(&id, &entity);
+        self.entities.insert(id, entity);
+    }
+    pub fn search(&self, term: &str) -> Vec<Identifier> {
+        self.search.search(term).into_iter().copied().collect()
+    }
+    pub fn get(&self, id: &Identifier) -> Option<&NamedEntity> {
+        self.entities.get(id)
+    }
+    pub fn values(&self) -> impl Iterator<Item = &NamedEntity> {
+        self.entities.values()
     }
 }
 
@@ -82,6 +401,88 @@ impl ByMainCategory {
     pub fn pois(&self) -> impl IntoIterator<Item = Identifier> {
         self.points_of_interest.get().into_iter()
     }
+
+    pub fn sequence(&self) -> RwSignal<Sequence> {
+        self.category.sequence()
+    }
+
+    pub fn category(&self) -> MainCategory {
+        self.category
+    }
+}
+
+/// In exclusive categories, a PoI without an end is taken to end when the next one
+/// starts. Only PoIs that can be placed in time take part.
+fn infer_ends(
+    cats: &IndexMap<Identifier, ByMainCategory>,
+    chronology: &Chronology,
+) -> HashMap<Identifier, Identifier> {
+    cats.values()
+        .filter(|c| c.sequence().get() == Sequence::Exclusive)
+        .flat_map(|c| {
+            let placed = chronology
+                .chronological(c.pois())
+                .into_iter()
+                .filter_map(|id| chronology.poi(&id))
+                .filter(|p| p.starts_within(chronology).is_ok())
+                .collect::<Vec<_>>();
+            placed
+                .windows(2)
+                .filter(|w| matches!(w[0].ends_as_of(chronology.as_of()), Ending::Undetermined))
+                .map(|w| (w[0].identity(), w[1].identity()))
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// The stages of school a person born when they were can be expected to have gone
+/// through, each from its first autumn term to its last spring term. All of them are
+/// tentative, to be confirmed or adjusted. Stages already `present` in the category
+/// are left out, so that filling it in again adds nothing twice.
+fn expected_schooling(
+    person: Identifier,
+    category: Identifier,
+    timeline: Identifier,
+    present: &HashMap<Stadium, Identifier>,
+) -> Vec<NonSignalPointOfInterest> {
+    let stages = [
+        (Stadium::Preschool, "Förskola"),
+        (Stadium::PreschoolClass, "Förskoleklass"),
+        (Stadium::Lower, "Lågstadiet"),
+        (Stadium::Middle, "Mellanstadiet"),
+        (Stadium::Upper, "Högstadiet"),
+        (Stadium::UpperSecondary, "Gymnasiet"),
+    ];
+    let mut pois = stages
+        .into_iter()
+        .map(|(stadium, name)| {
+            let poi = NonSignalPointOfInterest::new(category, name.to_string())
+                .within(timeline)
+                .tentative();
+            match stadium.grades() {
+                Some(grades) => poi
+                    .starting(PointInTime::TermOf(Term::Fall, *grades.start(), person))
+                    .ending(Ending::At(PointInTime::TermOf(
+                        Term::Spring,
+                        *grades.end(),
+                        person,
+                    ))),
+                // Förskolan från ett års ålder
+                None => poi.starting(PointInTime::ChronologicalAge(1, person)),
+            }
+        })
+        .collect::<Vec<_>>();
+    // Förskolan tar slut när förskoleklassen börjar, den som redan finns om någon gör det
+    let preschool_class = present
+        .get(&Stadium::PreschoolClass)
+        .copied()
+        .unwrap_or(pois[1].identity());
+    pois[0] = pois[0].clone().ending(Ending::Upon(preschool_class));
+    pois.into_iter()
+        .zip(stages)
+        .filter(|(_, (stadium, _))| !present.contains_key(stadium))
+        .map(|(poi, _)| poi)
+        .collect()
 }
 
 // INGRESS - load and split into categories - maintain state of active category for inserts
@@ -94,26 +495,107 @@ impl ByMainCategory {
 // TODO integrera indicium
 
 pub fn provide_timeline_context() {
-    let starting_categories = init_example_categories();
+    // Förväntad skolgång läggs här, vad kategorin än döps om till
+    let schooling = MainCategory::new_exclusive("Schooling".to_string());
+    let starting_categories = init_example_categories(schooling);
+    let schooling = schooling.identity();
     let person = Person::new(
         "Långben".to_string(),
-        PointInTime::Day(NaiveDate::from_ymd_opt(2007, 12, 30).unwrap()),
+        PointInTime::day(2007, 12, 30).unwrap_or_default(),
     );
     let timeline = Timeline::new(person.identity());
-    let pois = init_example_pois(&starting_categories, &timeline);
+    let informant = Person::new("Mamma".to_string(), PointInTime::Undetermined);
+    let interview = Source::new(
+        SourceKind::Interview,
+        PointInTime::day(2024, 3, 12).unwrap_or_default(),
+    )
+    .told_by(informant.identity());
+    let journal = Source::new(SourceKind::Journal, PointInTime::from(2009));
+    let clinic = NamedEntity::new("BVC Räveby".to_string(), EntityKind::Clinic);
+    let places = init_example_places();
+    let pois = init_example_pois(
+        &starting_categories,
+        &timeline,
+        &interview,
+        &journal,
+        &clinic,
+        &places,
+    );
 
     let cats = arrange_by_category(starting_categories, &pois.values().collect::<Vec<_>>());
 
     let pois = create_rw_signal(PoIs::from_poi_collection(pois));
+    let people = create_rw_signal(HashMap::from([
+        (person.identity(), person),
+        (informant.identity(), informant),
+    ]));
+    let entities = create_rw_signal(Entities::from_entities([clinic]));
+    let places = create_rw_signal(
+        places
+            .into_iter()
+            .map(|(p, _)| (p.identity(), p))
+            .collect::<HashMap<_, _>>(),
+    );
+    let sources = create_rw_signal(HashMap::from([
+        (interview.identity(), interview),
+        (journal.identity(), journal),
+    ]));
+    let timeline = create_rw_signal(timeline);
+    let chronology = Chronology {
+        pois,
+        people,
+        sources,
+        entities,
+        places,
+        timeline,
+        as_of: create_rw_signal(None),
+    };
+    // Nyckelorden räknas om när något de beror på ändras, men indexet skrivs bara
+    // om när de blivit andra, så att skrivningen inte sätter igång sig själv igen
+    let keywords = create_memo(move |_| chronology.keywords());
+    create_effect(move |_| keywords.with(|k| chronology.reindex(k)));
+    let solution = create_memo(move |_| chronology.solve());
+    let inferred_ends = create_memo(move |_| cats.with(|c| infer_ends(c, &chronology)));
+    let warnings = create_memo(move |_| cats.with(|c| check(c, &chronology)));
+    let style = create_rw_signal(Style::default());
+    let least_certain = create_rw_signal(None);
+    let tag_filter = create_rw_signal(None);
     let add_poi = create_callback_for_adding_poi(pois, cats);
-    let begins = Signal::derive(move || pois.with(|p| p.try_begins())); // Kan vara tom pga inga inlagda saker än
+    let add_expected_schooling = Callback::new(move |_| {
+        let Some(in_category) =
+            cats.with_untracked(|c| c.get(&schooling).map(|c| c.points_of_interest))
+        else {
+            return;
+        };
+        // De stadier som redan finns, hur de än har döpts om
+        let present = in_category.with_untracked(|ids| {
+            pois.with_untracked(|p| {
+                ids.iter()
+                    .filter_map(|id| p.get(id))
+                    .filter_map(|poi| poi.starts().stage().map(|s| (s, poi.identity())))
+                    .collect::<HashMap<_, _>>()
+            })
+        });
+        let (timeline, owner) = timeline.with_untracked(|t| (t.identity(), t.parent()));
+        for poi in expected_schooling(owner, schooling, timeline, &present) {
+            add_poi(poi);
+        }
+    });
+    let begins = Signal::derive(move || chronology.try_begins()); // Kan vara tom pga inga inlagda saker än
     let today = Local::now().date_naive();
     let span = Signal::derive(move || begins.get().map_or(TimeDelta::zero(), |e| today - e));
 
     let ctx = TimelineContext {
         pois,
+        chronology,
+        solution,
+        inferred_ends,
+        warnings,
+        style,
+        least_certain,
+        tag_filter,
         cats,
-        add_poi,
+        add_expected_schooling,
         span,
         begins,
     };
@@ -126,14 +608,15 @@ fn create_callback_for_adding_poi(
 ) -> Callback<NonSignalPointOfInterest> {
     let add_poi = move |p: NonSignalPointOfInterest| {
         let p: PointOfInterest = p.into();
+        // En PoI utan känd kategori skulle inte synas någonstans
+        let Some(cat) = cats.with_untracked(|c| c.get(&p.parent()).map(|c| c.points_of_interest))
+        else {
+            return;
+        };
         pois.update(|ps| {
             ps.insert(p);
         });
-        cats.with_untracked(|c| {
-            let cat = c.get(&p.parent()).unwrap();
-            cat.points_of_interest
-                .update(|pois| pois.push(p.identity()));
-        });
+        cat.update(|pois| pois.push(p.identity()));
     };
     Callback::new(with_current_owner(add_poi))
 }
@@ -166,29 +649,53 @@ fn arrange_by_category(
 }
 
 fn init_example_pois(
-    starting_categories: &Vec<MainCategory>,
+    starting_categories: &[MainCategory],
     timeline: &Timeline,
+    interview: &Source,
+    journal: &Source,
+    clinic: &NamedEntity,
+    places: &[(Place, PointInTime)],
 ) -> HashMap<Identifier, PointOfInterest> {
+    // Intervjun med Mamma, genomgången av journalen och ett uppföljande samtal
+    let session = |month, day| {
+        NaiveDate::from_ymd_opt(2024, month, day)
+            .and_then(|d| d.and_hms_opt(10, 0, 0))
+            .unwrap_or_default()
+    };
+    let (interviewed, read_journal, followed_up) = (session(3, 12), session(4, 2), session(5, 1));
     let pois = vec![
         vec![(
             "Födsel",
-            PointInTime::from(NaiveDate::from_ymd_opt(2007, 12, 30).unwrap()),
+            PointInTime::day(2007, 12, 30).unwrap_or_default(),
+            interviewed,
         )],
         vec![
-            ("Gick", PointInTime::from(2008)),
-            ("Talade", PointInTime::from(2008)),
-            ("Cyklade", PointInTime::from(2013)),
-            ("Simmade", PointInTime::from(2014)),
-        ],
-        vec![
-            ("Hammarstad", PointInTime::from(2007)),
-            ("Räveby", PointInTime::from(2009)),
-            ("Ingalunda", PointInTime::from(2014)),
+            ("Talade", PointInTime::from(2008), interviewed),
+            (
+                "Cyklade",
+                PointInTime::ChronologicalAge(5, timeline.parent()),
+                interviewed,
+            ),
+            (
+                "Simmade",
+                PointInTime::ago(Duration::Years(10), followed_up.date()),
+                followed_up,
+            ),
         ],
+        // Boendena läggs till med sina platser
+        vec![],
         vec![
-            ("Förskola", PointInTime::from(2009)),
-            ("Lågstadiet på Gladskolan", PointInTime::from(2014)),
-            ("Mellanstadiet på snejipan", PointInTime::from(2017)),
+            ("Förskola", PointInTime::from(2009), interviewed),
+            (
+                "Lågstadiet på Gladskolan",
+                PointInTime::from(2014),
+                followed_up,
+            ),
+            (
+                "Mellanstadiet på snejipan",
+                PointInTime::from(2017),
+                followed_up,
+            ),
         ],
     ];
     // Categories -> HAshmap<Identifier, Signal<Vec<Identifier>>>
@@ -198,31 +705,460 @@ fn init_example_pois(
     starting_categories
         .iter()
         .map(|v| v.identity())
-        .zip(pois.into_iter())
-        .map(|(p, ps)| {
-            ps.into_iter()
-                .map(|(poi, st)| {
-                    PointOfInterest::new_bare_with_start(
-                        p,
-                        timeline.identity(),
-                        poi.to_string(),
-                        st,
-                    )
-                })
-                .collect::<Vec<_>>()
+        .zip(pois)
+        .flat_map(|(p, ps)| {
+            ps.into_iter().map(move |(poi, st, recorded)| {
+                PointOfInterest::from(
+                    NonSignalPointOfInterest::new(p, poi.to_string())
+                        .within(timeline.identity())
+                        .starting(st)
+                        .recorded(recorded),
+                )
+            })
         })
-        .flatten()
+        .chain(starting_categories.get(2).into_iter().flat_map(|c| {
+            places.iter().map(|(place, moved_in)| {
+                NonSignalPointOfInterest::new(c.identity(), place.name().get())
+                    .within(timeline.identity())
+                    .starting(*moved_in)
+                    .at(place.identity())
+                    .recorded(interviewed)
+                    .into()
+            })
+        }))
+        .chain(starting_categories.first().map(|c| {
+            NonSignalPointOfInterest::new(c.identity(), "Somrar hos mormor".to_string())
+                .within(timeline.identity())
+                .starting(PointInTime::from(2010))
+                .ending(Ending::At(PointInTime::from(2015)))
+                .recurring(Recurrence::EverySeason(Season::Summer))
+                .calibrated(Calibration {
+                    date: Certainty::Uncertain,
+                    margin: Some(Duration::Years(1)),
+                    ..Default::default()
+                })
+                .citing(interview.identity())
+                .recorded(interviewed)
+                .into()
+        }))
+        // Mamma minns det ett år tidigare än BVC
+        .chain(starting_categories.get(1).map(|c| {
+            let walked = NonSignalPointOfInterest::new(c.identity(), "Gick".to_string())
+                .within(timeline.identity())
+                .claiming(Claim {
+                    source: interview.identity(),
+                    starts: PointInTime::from(2008),
+                })
+                .claiming(Claim {
+                    source: journal.identity(),
+                    starts: PointInTime::from(2009),
+                })
+                .preferring(journal.identity())
+                .mentioning(clinic.identity())
+                .recorded(read_journal);
+            match Tag::new("utveckling/motorik") {
+                Some(tag) => walked.tagged(tag),
+                None => walked,
+            }
+            .into()
+        }))
         .map(|p| (p.identity(), p))
         .collect::<HashMap<Identifier, PointOfInterest>>()
 }
 
-fn init_example_categories() -> Vec<MainCategory> {
+fn init_example_places() -> Vec<(Place, PointInTime)> {
+    let place = |town: &str, latitude, longitude| {
+        Place::new()
+            .in_town(town.to_string())
+            .in_municipality(format!("{town}s kommun"))
+            .in_country("Sverige".to_string())
+            .located(Coordinates {
+                latitude,
+                longitude,
+            })
+    };
+    vec![
+        (place("Hammarstad", 59.86, 17.64), PointInTime::from(2007)),
+        (place("Räveby", 58.41, 15.62), PointInTime::from(2009)),
+        (
+            place("Ingalunda", 57.71, 11.97).at_address("Storgatan 1".to_string()),
+            PointInTime::from(2014),
+        ),
+    ]
+}
+
+fn init_example_categories(schooling: MainCategory) -> Vec<MainCategory> {
     let starting_categories = vec![
         MainCategory::new("Life events".to_string()),
         MainCategory::new("Developmental steps".to_string()),
-        MainCategory::new("Places lived".to_string()),
-        MainCategory::new("Schooling".to_string()),
+        MainCategory::new_exclusive("Places lived".to_string()),
+        schooling,
     ];
     starting_categories
 }
 
+#[cfg(test)]
+mod tests {
+    use leptos::{create_runtime, SignalSet};
+
+    use super::*;
+    use crate::{parse::parse, types::TimeError};
+
+    fn chronology(person: Person, pois: Vec<PointOfInterest>) -> Chronology {
+        let timeline = Timeline::new(person.identity());
+        Chronology {
+            pois: create_rw_signal(PoIs::from_poi_collection(
+                pois.into_iter().map(|p| (p.identity(), p)).collect(),
+            )),
+            people: create_rw_signal(HashMap::from([(person.identity(), person)])),
+            sources: create_rw_signal(HashMap::new()),
+            entities: create_rw_signal(Entities::from_entities([])),
+            places: create_rw_signal(HashMap::new()),
+            timeline: create_rw_signal(timeline),
+            as_of: create_rw_signal(None),
+        }
+    }
+
+    fn poi(category: &MainCategory, name: &str, starts: PointInTime) -> PointOfInterest {
+        PointOfInterest::new_bare_with_start(
+            category.identity(),
+            Identifier::default(),
+            name.to_string(),
+            starts,
+        )
+    }
+
+    /// What the checker finds in the PoIs of a single category
+    fn warnings(category: MainCategory, pois: Vec<PointOfInterest>) -> Vec<Warning> {
+        let person = Person::new("Långben".to_string(), PointInTime::from(2007));
+        let cats = arrange_by_category(vec![category], &pois.iter().collect());
+        let chronology = chronology(person, pois);
+        cats.with(|c| check(c, &chronology))
+    }
+
+    #[test]
+    fn indexes_ages_by_the_years_they_resolve_to() {
+        let runtime = create_runtime();
+        let person = Person::new("Långben".to_string(), PointInTime::from(2007));
+        let owner = person.identity();
+        let cycled = PointOfInterest::new_bare_with_start(
+            Identifier::default(),
+            Identifier::default(),
+            "Cyklade".to_string(),
+            PointInTime::ChronologicalAge(5, owner),
+        );
+        let id = cycled.identity();
+        let chronology = chronology(person, vec![cycled]);
+        assert!(chronology.pois.with(|p| p.search("2012")).is_empty());
+
+        chronology.reindex(&chronology.keywords());
+        assert_eq!(chronology.pois.with(|p| p.search("2012")), vec![id]);
+
+        // Flyttas födseln följer åldern med
+        chronology.people.update(|p| {
+            if let Some(p) = p.get_mut(&owner) {
+                p.set_origo(PointInTime::from(2010));
+            }
+        });
+        chronology.reindex(&chronology.keywords());
+        let keywords = chronology.pois.with(|p| p.keywords[&id].clone());
+        assert_eq!(keywords.0, ["Cyklade", "2015", "2016"]);
+        assert_eq!(chronology.pois.with(|p| p.search("2015")), vec![id]);
+        runtime.dispose();
+    }
+
+    #[test]
+    fn infers_open_ends_from_the_next_poi_in_exclusive_categories() {
+        let runtime = create_runtime();
+        let person = Person::new("Långben".to_string(), PointInTime::from(2007));
+        let places = MainCategory::new("Places lived".to_string());
+        let events = MainCategory::new("Life events".to_string());
+        places.sequence().set(Sequence::Exclusive);
+        let hammarstad = poi(&places, "Hammarstad", PointInTime::from(2007));
+        let raveby = poi(&places, "Räveby", PointInTime::from(2009));
+        let ingalunda = poi(&places, "Ingalunda", PointInTime::from(2014));
+        let walked = poi(&events, "Gick", PointInTime::from(2008));
+        let talked = poi(&events, "Talade", PointInTime::from(2009));
+        // Ett slut som redan är givet lämnas som det är
+        raveby.set_ends(Ending::At(PointInTime::from(2013)));
+        let all = vec![hammarstad, raveby, ingalunda, walked, talked];
+        let cats = arrange_by_category(vec![places, events], &all.iter().collect());
+        let chronology = chronology(person, all);
+
+        let inferred = cats.with(|c| infer_ends(c, &chronology));
+        assert_eq!(
+            inferred,
+            HashMap::from([(hammarstad.identity(), raveby.identity())])
+        );
+        assert_eq!(
+            Ending::Upon(inferred[&hammarstad.identity()]).resolve(&hammarstad, &chronology),
+            raveby.starts_within(&chronology)
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn warns_about_what_cannot_be_placed() {
+        let runtime = create_runtime();
+        let events = MainCategory::new("Life events".to_string());
+        let stranger = Identifier::default();
+        let cycled = poi(
+            &events,
+            "Cyklade",
+            PointInTime::ChronologicalAge(5, stranger),
+        );
+        // Det som inte alls är daterat är inget fel
+        let undated = poi(&events, "Simmade", PointInTime::Undetermined);
+        assert_eq!(
+            warnings(events, vec![cycled, undated]),
+            vec![Warning::Unplaced {
+                poi: cycled.identity(),
+                error: TimeError::UnknownPerson(stranger),
+            }]
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn warns_about_what_starts_before_birth() {
+        let runtime = create_runtime();
+        let events = MainCategory::new("Life events".to_string());
+        let early = poi(&events, "Gick", PointInTime::from(2005));
+        let born = poi(&events, "Födsel", PointInTime::from(2007));
+        assert_eq!(
+            warnings(events, vec![early, born]),
+            vec![Warning::BeforeBirth {
+                poi: early.identity()
+            }]
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn warns_about_what_ends_before_it_starts() {
+        let runtime = create_runtime();
+        let events = MainCategory::new("Life events".to_string());
+        let backwards = poi(&events, "Förskola", PointInTime::from(2010));
+        backwards.set_ends(Ending::At(PointInTime::from(2008)));
+        // Ett slut samma år kan mycket väl ligga efter början
+        let same_year = poi(&events, "Simskola", PointInTime::from(2010));
+        same_year.set_ends(Ending::At(PointInTime::from(2010)));
+        assert_eq!(
+            warnings(events, vec![backwards, same_year]),
+            vec![Warning::EndsBeforeStart {
+                poi: backwards.identity()
+            }]
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn warns_about_overlaps_only_where_one_goes_on_at_a_time() {
+        let runtime = create_runtime();
+        let places = MainCategory::new("Places lived".to_string());
+        let hammarstad = poi(&places, "Hammarstad", PointInTime::from(2007));
+        let raveby = poi(&places, "Räveby", PointInTime::from(2009));
+        hammarstad.set_ends(Ending::At(PointInTime::from(2012)));
+        let category = places.identity();
+        assert!(warnings(places, vec![hammarstad, raveby]).is_empty());
+
+        places.sequence().set(Sequence::Exclusive);
+        assert_eq!(
+            warnings(places, vec![hammarstad, raveby]),
+            vec![Warning::Overlaps {
+                poi: raveby.identity(),
+                other: hammarstad.identity(),
+                category,
+            }]
+        );
+        // Ett öppet slut varar till nästa början, vilket inte är en överlappning
+        let ingalunda = poi(&places, "Ingalunda", PointInTime::from(2014));
+        let vasterby = poi(&places, "Västerby", PointInTime::from(2016));
+        assert!(warnings(places, vec![ingalunda, vasterby]).is_empty());
+        runtime.dispose();
+    }
+
+    #[test]
+    fn warns_about_disputed_accounts() {
+        let runtime = create_runtime();
+        let events = MainCategory::new("Life events".to_string());
+        let walked = poi(&events, "Gick", PointInTime::Undetermined);
+        let (mother, journal) = (Identifier::default(), Identifier::default());
+        walked.claim(Claim {
+            source: mother,
+            starts: PointInTime::from(2008),
+        });
+        walked.claim(Claim {
+            source: journal,
+            starts: PointInTime::from(2009),
+        });
+        // Samma år från båda håll är ingen motsägelse
+        let talked = poi(&events, "Talade", PointInTime::Undetermined);
+        talked.claim(Claim {
+            source: mother,
+            starts: PointInTime::from(2009),
+        });
+        talked.claim(Claim {
+            source: journal,
+            starts: PointInTime::from(2009),
+        });
+        assert_eq!(
+            warnings(events, vec![walked, talked]),
+            vec![Warning::Disputed {
+                poi: walked.identity()
+            }]
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn adds_only_the_stages_of_school_not_already_there() {
+        let runtime = create_runtime();
+        let (person, category, timeline) = Default::default();
+        let names = |pois: &[NonSignalPointOfInterest]| {
+            pois.iter()
+                .map(|p| PointOfInterest::from(p.clone()).name().get())
+                .collect::<Vec<_>>()
+        };
+        let all = expected_schooling(person, category, timeline, &HashMap::new());
+        assert_eq!(all.len(), 6);
+
+        // Omdöpt, men fortfarande förskoleklass
+        let renamed = poi(
+            &MainCategory::new("Schooling".to_string()),
+            "Sexårs",
+            PointInTime::TermOf(Term::Fall, 0, person),
+        );
+        let stage = renamed.starts().stage().unwrap();
+        let present = HashMap::from([(stage, renamed.identity())]);
+        let rest = expected_schooling(person, category, timeline, &present);
+        assert_eq!(
+            names(&rest),
+            [
+                "Förskola",
+                "Lågstadiet",
+                "Mellanstadiet",
+                "Högstadiet",
+                "Gymnasiet"
+            ]
+        );
+        // Förskolan slutar när den förskoleklass som redan fanns börjar
+        let preschool = PointOfInterest::from(rest[0].clone());
+        assert!(matches!(
+            preschool.ends(),
+            Ending::Upon(id) if id == renamed.identity()
+        ));
+
+        let present = all
+            .into_iter()
+            .map(PointOfInterest::from)
+            .filter_map(|p| p.starts().stage().map(|s| (s, p.identity())))
+            .collect();
+        assert!(expected_schooling(person, category, timeline, &present).is_empty());
+        runtime.dispose();
+    }
+
+    #[test]
+    fn traces_pois_back_to_their_sources_in_order() {
+        let runtime = create_runtime();
+        let category = MainCategory::new("Boende".to_string());
+        let moved = poi(&category, "Flytten", PointInTime::from(2012));
+        let born = poi(&category, "Födsel", PointInTime::from(2007));
+        let person = Person::new("Långben".to_string(), PointInTime::from(2007));
+        let chronology = chronology(person, vec![moved, born]);
+        let interview = Source::new(SourceKind::Interview, PointInTime::from(2024));
+        let source = interview.identity();
+        moved.cite(source);
+        moved.cite(source);
+        born.cite(source);
+        assert_eq!(moved.sources(), vec![source]);
+        assert_eq!(
+            chronology.cited_by(&source),
+            vec![born.identity(), moved.identity()]
+        );
+        born.uncite(&source);
+        assert!(!born.cites(&source));
+        assert_eq!(chronology.cited_by(&source), vec![moved.identity()]);
+        runtime.dispose();
+    }
+
+    #[test]
+    fn finds_pois_by_what_they_mention() {
+        let runtime = create_runtime();
+        let person = Person::new("Långben".to_string(), PointInTime::from(2007));
+        let clinic = NamedEntity::new("BVC Räveby".to_string(), EntityKind::Clinic);
+        let walked: PointOfInterest =
+            NonSignalPointOfInterest::new(Identifier::default(), "Gick".to_string())
+                .mentioning(clinic.identity())
+                .into();
+        let (id, mentioned) = (walked.identity(), clinic.identity());
+        let chronology = chronology(person, vec![walked]);
+        chronology.entities.update(|e| e.insert(clinic));
+        assert_eq!(
+            chronology.entities.with(|e| e.search("BVC")),
+            vec![mentioned]
+        );
+        assert_eq!(chronology.mentioning(&mentioned), vec![id]);
+        chronology.reindex(&chronology.keywords());
+        assert_eq!(chronology.pois.with(|p| p.search("BVC Räveby")), vec![id]);
+        runtime.dispose();
+    }
+
+    #[test]
+    fn ends_the_last_stay_on_the_day_seen_as_of() {
+        let runtime = create_runtime();
+        let person = Person::new("Långben".to_string(), PointInTime::from(2007));
+        let place = Place::new().in_town("Räveby".to_string());
+        let day = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let lived: PointOfInterest =
+            NonSignalPointOfInterest::new(Identifier::default(), "Räveby".to_string())
+                .starting(PointInTime::Day(day(2010, 1, 1)))
+                .at(place.identity())
+                .recorded(day(2010, 1, 1).and_hms_opt(10, 0, 0).unwrap())
+                .into();
+        let id = lived.identity();
+        let chronology = chronology(person, vec![lived]);
+        chronology.as_of.set(day(2012, 1, 1).and_hms_opt(12, 0, 0));
+        let residences = chronology.residences([id]);
+        assert_eq!(
+            residences.stays[&place.identity()],
+            day(2012, 1, 1) - day(2010, 1, 1)
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn counts_time_ago_from_the_day_seen_as_of() {
+        let runtime = create_runtime();
+        let person = Person::new("Långben".to_string(), PointInTime::from(2007));
+        let chronology = chronology(person, vec![]);
+        let day = NaiveDate::from_ymd_opt(2012, 1, 1).unwrap();
+        chronology.as_of.set(day.and_hms_opt(12, 0, 0));
+        assert_eq!(
+            parse("3 år sedan", &chronology.parse_context(), &chronology),
+            Ok(PointInTime::ago(Duration::Years(3), day))
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn finds_pois_by_tags_added_later() {
+        let runtime = create_runtime();
+        let person = Person::new("Långben".to_string(), PointInTime::from(2007));
+        let slept: PointOfInterest =
+            NonSignalPointOfInterest::new(Identifier::default(), "Sov gott".to_string()).into();
+        let id = slept.identity();
+        let chronology = chronology(person, vec![slept]);
+        assert!(chronology.pois.with(|p| p.search("sömn")).is_empty());
+
+        let tag = Tag::new("hälsa/sömn").unwrap();
+        slept.tag(tag.clone());
+        chronology.reindex(&chronology.keywords());
+        assert_eq!(chronology.pois.with(|p| p.search("sömn")), vec![id]);
+
+        slept.untag(&tag);
+        chronology.reindex(&chronology.keywords());
+        assert!(chronology.pois.with(|p| p.search("sömn")).is_empty());
+        runtime.dispose();
+    }
+}
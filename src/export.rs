@@ -0,0 +1,45 @@
+use indexmap::IndexMap;
+use leptos::SignalGet;
+
+use crate::{
+    data::{ByMainCategory, Chronology},
+    format::{Locale, Style},
+    types::{Identifier, Lookup, MyName},
+};
+
+/// The whole timeline as plain text, category by category and in the order
+/// things happened
+pub fn as_text(
+    cats: &IndexMap<Identifier, ByMainCategory>,
+    chronology: &Chronology,
+    style: &Style,
+) -> String {
+    let source = match style.locale {
+        Locale::Swedish => "Källa",
+        Locale::English => "Source",
+    };
+    cats.values()
+        .map(|c| {
+            let pois = chronology
+                .chronological(c.pois())
+                .into_iter()
+                .filter_map(|id| chronology.poi(&id))
+                .map(|p| {
+                    let sources = p
+                        .sources()
+                        .iter()
+                        .filter_map(|s| chronology.source(s))
+                        .map(|s| format!("  {source}: {}\n", style.source(&s, chronology)))
+                        .collect::<String>();
+                    format!(
+                        "- {}: {}\n{sources}",
+                        style.period(&p, chronology),
+                        p.name().get()
+                    )
+                })
+                .collect::<String>();
+            format!("{}\n{pois}", c.name().get())
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
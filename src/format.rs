@@ -0,0 +1,725 @@
+use chrono::{Datelike, NaiveDate, TimeDelta};
+use leptos::SignalGet;
+
+use crate::{
+    parse::ParseError,
+    types::{
+        Approximated, Bound, Calibration, Certainty, Constraint, Duration, Ending, EntityKind,
+        Identifier, Interval, Lookup, Month, MyName, Offset, Place, PointInTime, PointOfInterest,
+        RangeEnd, Recurrence, Reliability, Season, Source, SourceKind, Stadium, Term, TimeError,
+    },
+};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Locale {
+    #[default]
+    Swedish,
+    English,
+}
+
+/// How points in time are written out for people to read. Each is written at
+/// the precision it was given in, so a season stays a season.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    pub locale: Locale,
+    /// Whether to add how old the timeline's person was at the time
+    pub with_age: bool,
+}
+
+impl Style {
+    pub(crate) fn sv_en(&self, sv: &'static str, en: &'static str) -> &'static str {
+        match self.locale {
+            Locale::Swedish => sv,
+            Locale::English => en,
+        }
+    }
+
+    /// "sommaren 2013", "ca 2008", "vid 5 års ålder" and so on
+    pub fn point_in_time(&self, p: &PointInTime, lookup: &impl Lookup) -> String {
+        let text = self.as_told(p, lookup);
+        let age = (self.with_age && !matches!(p, PointInTime::ChronologicalAge(..)))
+            .then(|| p.resolve(lookup).ok())
+            .flatten()
+            .and_then(|i| self.age_at(&i, lookup));
+        match age {
+            Some(age) => format!("{text} ({age})"),
+            None => text,
+        }
+    }
+
+    fn as_told(&self, p: &PointInTime, lookup: &impl Lookup) -> String {
+        match p {
+            PointInTime::Time(t) => {
+                let at = self.sv_en("kl.", "at");
+                format!("{} {at} {}", self.date(&t.date()), t.format("%H:%M"))
+            }
+            PointInTime::Day(d) => self.date(d),
+            PointInTime::Week(y, w) => match self.locale {
+                Locale::Swedish => format!("vecka {w} {y}"),
+                Locale::English => format!("week {w} of {y}"),
+            },
+            PointInTime::Range(RangeEnd::Day(from), RangeEnd::Day(to)) => {
+                self.interval(&Interval::new(*from, *to))
+            }
+            PointInTime::Range(from, to) => format!(
+                "{} – {}",
+                self.as_told(&PointInTime::from(*from), lookup),
+                self.as_told(&PointInTime::from(*to), lookup)
+            ),
+            PointInTime::Month(y, m) => format!("{} {y}", self.month(m)),
+            PointInTime::Season(y, s) => format!("{} {y}", self.season(s)),
+            PointInTime::Year(y) => y.to_string(),
+            PointInTime::ChronologicalAge(age, _) => match self.locale {
+                Locale::Swedish => format!("vid {age} års ålder"),
+                Locale::English => format!("at age {age}"),
+            },
+            // Som det sades, och när det blir, lika avrundat som det sades
+            PointInTime::TimeAgo { at, time } => {
+                let about = self.sv_en("ca", "c.");
+                let given = self.as_given(p, lookup).unwrap_or_default();
+                match time.before(*at) {
+                    Some(then) => match time {
+                        Duration::Years(_) => format!("{given} ({about} {})", then.year()),
+                        Duration::Months(_) => {
+                            format!("{given} ({about} {} {})", self.month_of(&then), then.year())
+                        }
+                        Duration::Weeks(_) | Duration::Days(_) => {
+                            format!("{given} ({about} {})", self.date(&then))
+                        }
+                    },
+                    None => self.duration_ago(time),
+                }
+            }
+            PointInTime::RelativeTo(..) | PointInTime::Approximated(_) => {
+                self.as_given(p, lookup).unwrap_or_default()
+            }
+            PointInTime::Grade(grade, _) => self.grade(*grade),
+            PointInTime::TermOf(term, grade, _) => {
+                let of = self.sv_en("i", "of");
+                format!("{} {of} {}", self.term(term), self.grade(*grade))
+            }
+            PointInTime::Stadium(stadium, _) => self.stadium(stadium).to_string(),
+            PointInTime::Undetermined => self.sv_en("okänt", "unknown").to_string(),
+        }
+    }
+
+    /// How a point in time given relative to something else was told, e.g. "3 år
+    /// före 12 mars 2024" or "2 år efter Flytt". Nothing for those that are dates in
+    /// their own right.
+    pub fn as_given(&self, p: &PointInTime, lookup: &impl Lookup) -> Option<String> {
+        match p {
+            PointInTime::TimeAgo { at, time } => {
+                let before = self.sv_en("före", "before");
+                Some(format!(
+                    "{} {before} {}",
+                    self.duration(time),
+                    self.date(at)
+                ))
+            }
+            PointInTime::RelativeTo(anchor, offset) => Some(format!(
+                "{} {}",
+                self.offset(offset),
+                self.name(anchor, lookup)
+            )),
+            PointInTime::Approximated(a) => Some(self.approximated(a, lookup)),
+            _ => None,
+        }
+    }
+
+    /// How the PoI ends, on its own
+    pub fn ending(&self, e: &Ending, lookup: &impl Lookup) -> Option<String> {
+        match e {
+            Ending::At(p) => Some(self.point_in_time(p, lookup)),
+            Ending::After(d) => Some(match self.locale {
+                Locale::Swedish => format!("i {}", self.duration(d)),
+                Locale::English => format!("for {}", self.duration(d)),
+            }),
+            Ending::Upon(next) => {
+                let until = self.sv_en("tills", "until");
+                Some(format!("{until} {}", self.name(next, lookup)))
+            }
+            Ending::Ongoing => Some(self.sv_en("pågår", "ongoing").to_string()),
+            Ending::Undetermined => None,
+        }
+    }
+
+    /// From when to when the PoI went on, e.g. "2009–2014"
+    pub fn period(&self, poi: &PointOfInterest, lookup: &impl Lookup) -> String {
+        match poi.recurs() {
+            Some(r) => format!("{}, {}", self.recurrence(&r), self.once(poi, lookup)),
+            None => self.once(poi, lookup),
+        }
+    }
+
+    fn once(&self, poi: &PointOfInterest, lookup: &impl Lookup) -> String {
+        let starts = self.point_in_time(&poi.starts_as_of(lookup.as_of()), lookup);
+        // Det som bara är ungefärligt känt skrivs som ungefärligt
+        let starts = if poi.calibration().is_some_and(|c| c.is_rough()) {
+            format!("{} {starts}", self.sv_en("ca", "around"))
+        } else {
+            starts
+        };
+        match poi.ends_as_of(lookup.as_of()) {
+            Ending::At(p) => {
+                let ends = self.as_told(&p, lookup);
+                // Korta datum binds ihop, längre får luft runt strecket
+                if starts.contains(' ') || ends.contains(' ') {
+                    format!("{starts} – {ends}")
+                } else {
+                    format!("{starts}–{ends}")
+                }
+            }
+            Ending::Ongoing => match self.locale {
+                Locale::Swedish => format!("sedan {starts}"),
+                Locale::English => format!("since {starts}"),
+            },
+            ends => match self.ending(&ends, lookup) {
+                Some(ends) => format!("{starts}, {ends}"),
+                None => starts,
+            },
+        }
+    }
+
+    /// Dates written as coarsely as they allow: whole years as years, whole
+    /// months as months
+    pub fn interval(&self, i: &Interval) -> String {
+        let (e, l) = (i.earliest(), i.latest());
+        let whole_years = (e.month(), e.day()) == (1, 1) && (l.month(), l.day()) == (12, 31);
+        let whole_months = e.day() == 1 && l.succ_opt().is_some_and(|n| n.day() == 1);
+        if whole_years && e.year() == l.year() {
+            e.year().to_string()
+        } else if whole_years {
+            format!("{}–{}", e.year(), l.year())
+        } else if whole_months && (e.year(), e.month()) == (l.year(), l.month()) {
+            format!("{} {}", self.month_of(&e), e.year())
+        } else if whole_months && e.year() == l.year() {
+            format!("{}–{} {}", self.month_of(&e), self.month_of(&l), e.year())
+        } else if whole_months {
+            format!(
+                "{} {} – {} {}",
+                self.month_of(&e),
+                e.year(),
+                self.month_of(&l),
+                l.year()
+            )
+        } else if i.is_exact() {
+            self.date(&e)
+        } else if (e.year(), e.month()) == (l.year(), l.month()) {
+            format!("{}–{}", e.day(), self.date(&l))
+        } else if e.year() == l.year() {
+            format!("{} {} – {}", e.day(), self.month_of(&e), self.date(&l))
+        } else {
+            format!("{} – {}", self.date(&e), self.date(&l))
+        }
+    }
+
+    /// Why a point in time could not be placed
+    pub fn error(&self, e: &TimeError, lookup: &impl Lookup) -> String {
+        match (self.locale, e) {
+            (Locale::Swedish, TimeError::Undetermined) => "Ingen tid angiven".to_string(),
+            (Locale::Swedish, TimeError::UnknownPerson(id)) => {
+                format!("Syftar på en okänd person ({id})")
+            }
+            (Locale::Swedish, TimeError::OutOfRange) => "Inget giltigt datum".to_string(),
+            (Locale::Swedish, TimeError::UnknownPoI(id)) => {
+                format!("Räknas från en okänd händelse ({id})")
+            }
+            (Locale::Swedish, TimeError::Cycle(id)) => {
+                format!("Räknas från sig själv, via {}", self.name(id, lookup))
+            }
+            (Locale::Swedish, TimeError::Unbounded) => {
+                "Begränsas bara åt ena hållet, och inget födelsedatum begränsar det andra"
+                    .to_string()
+            }
+            (Locale::Swedish, TimeError::Contradiction(a, b)) => format!(
+                "Kan inte vara både {} och {} det som angavs",
+                self.constraint(a),
+                self.constraint(b)
+            ),
+            (Locale::Swedish, TimeError::InvalidMonth(m)) => format!("Det finns ingen månad {m}"),
+            (Locale::Swedish, TimeError::InvalidDate(y, m, d)) => {
+                format!("{y}-{m:02}-{d:02} är inget datum")
+            }
+            (Locale::Swedish, TimeError::InvalidWeek(y, w)) => format!("{y} har ingen vecka {w}"),
+            (Locale::Swedish, TimeError::ReversedRange) => "Slutar innan den börjar".to_string(),
+            (Locale::English, e) => e.to_string(),
+        }
+    }
+
+    /// Why typed text could not be read as a point in time
+    pub fn parse_error(&self, e: &ParseError, lookup: &impl Lookup) -> String {
+        match (self.locale, e) {
+            (_, ParseError::Time(e)) => self.error(e, lookup),
+            (Locale::Swedish, ParseError::Empty) => "Inget att läsa".to_string(),
+            (Locale::Swedish, ParseError::Unknown(w)) => format!("Förstod inte \"{w}\""),
+            (Locale::Swedish, ParseError::MissingYear(w)) => {
+                format!("\"{w}\" behöver ett år, t.ex. \"{w} 2014\"")
+            }
+            (Locale::Swedish, ParseError::MissingNumber(e)) => {
+                format!("Behöver ett tal, t.ex. \"{e}\"")
+            }
+            (Locale::Swedish, ParseError::MissingUnit) => {
+                "Behöver en enhet, t.ex. år eller månader".to_string()
+            }
+            (Locale::Swedish, ParseError::InvalidDate(d)) => format!("\"{d}\" är inget datum"),
+            (Locale::English, e) => e.to_string(),
+        }
+    }
+
+    /// How old the timeline's person was, as a range where it is not certain
+    fn age_at(&self, i: &Interval, lookup: &impl Lookup) -> Option<String> {
+        let born = lookup.origo()?;
+        let youngest = full_years(born.latest(), i.earliest());
+        let oldest = full_years(born.earliest(), i.latest());
+        if oldest < 0 {
+            return None;
+        }
+        let age = if youngest.max(0) == oldest {
+            oldest.to_string()
+        } else {
+            format!("{}–{oldest}", youngest.max(0))
+        };
+        Some(match self.locale {
+            Locale::Swedish => format!("{age} år"),
+            Locale::English => format!("age {age}"),
+        })
+    }
+
+    /// "ihågkommet, osäkert ±1 år" and so on
+    pub fn calibration(&self, c: &Calibration) -> String {
+        let mut told = format!(
+            "{}, {}",
+            self.reliability(&c.reliability),
+            self.certainty(&c.date)
+        );
+        if let Some(margin) = c.margin {
+            told += &format!(" ±{}", self.duration(&margin));
+        }
+        if c.content != c.date {
+            let content = self.sv_en("innehållet", "content");
+            told += &format!(", {content} {}", self.certainty(&c.content));
+        }
+        told
+    }
+
+    /// "intervju med Mamma, 12 mars 2024" and so on
+    pub fn source(&self, s: &Source, lookup: &impl Lookup) -> String {
+        let mut told = self.source_kind(&s.kind()).to_string();
+        if let Some(informant) = s.informant().and_then(|i| lookup.person(&i)) {
+            let by = match s.kind() {
+                SourceKind::Interview => self.sv_en("med", "with"),
+                _ => self.sv_en("från", "from"),
+            };
+            told += &format!(" {by} {}", informant.name().get());
+        }
+        if !matches!(s.date(), PointInTime::Undetermined) {
+            told += &format!(", {}", self.as_told(s.date(), lookup));
+        }
+        told
+    }
+
+    pub fn source_kind(&self, k: &SourceKind) -> &'static str {
+        match k {
+            SourceKind::Interview => self.sv_en("intervju", "interview"),
+            SourceKind::SchoolRecords => self.sv_en("skolhandlingar", "school records"),
+            SourceKind::Journal => self.sv_en("journal", "journal"),
+            SourceKind::Other => self.sv_en("annan källa", "other source"),
+        }
+    }
+
+    pub fn reliability(&self, r: &Reliability) -> &'static str {
+        match r {
+            Reliability::Hearsay => self.sv_en("hörsägen", "hearsay"),
+            Reliability::Remembered => self.sv_en("ihågkommet", "remembered"),
+            Reliability::Witnessed => self.sv_en("bevittnat", "witnessed"),
+            Reliability::Documented => self.sv_en("dokumenterat", "documented"),
+        }
+    }
+
+    /// "Storgatan 1, Ingalunda, Sverige", leaving out a municipality named after its town
+    pub fn place(&self, p: &Place) -> String {
+        // Bara "Ingalunda kommun" eller "Ingalundas kommun", inte "Åsele kommun" efter "Ås"
+        let named_after_town = p
+            .town()
+            .map(|t| [format!("{t}s kommun"), format!("{t} kommun")])
+            .unwrap_or_default();
+        p.levels()
+            .filter(|l| !named_after_town.contains(l))
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    /// A length of time as coarsely as it is told, e.g. "5 år" or "3 månader"
+    pub fn length(&self, t: &TimeDelta) -> String {
+        let days = t.num_days();
+        let coarse = match days {
+            365.. => Duration::Years((days / 365) as u32),
+            30.. => Duration::Months((days / 30) as u32),
+            _ => Duration::Days(days.max(0) as u32),
+        };
+        self.duration(&coarse)
+    }
+
+    /// "skola", "mottagning" and so on
+    pub fn entity_kind(&self, k: &EntityKind) -> &'static str {
+        match k {
+            EntityKind::Person => self.sv_en("person", "person"),
+            EntityKind::School => self.sv_en("skola", "school"),
+            EntityKind::Clinic => self.sv_en("mottagning", "clinic"),
+            EntityKind::Employer => self.sv_en("arbetsgivare", "employer"),
+            EntityKind::Organisation => self.sv_en("organisation", "organisation"),
+            EntityKind::Place => self.sv_en("plats", "place"),
+        }
+    }
+
+    pub fn certainty(&self, c: &Certainty) -> &'static str {
+        match c {
+            Certainty::Guess => self.sv_en("gissat", "a guess"),
+            Certainty::Uncertain => self.sv_en("osäkert", "uncertain"),
+            Certainty::Likely => self.sv_en("troligt", "likely"),
+            Certainty::Certain => self.sv_en("säkert", "certain"),
+        }
+    }
+
+    /// "varje sommar", "var 3:e vecka" and so on
+    pub fn recurrence(&self, r: &Recurrence) -> String {
+        match (self.locale, r) {
+            (Locale::Swedish, Recurrence::EverySeason(s)) => {
+                let season = match s {
+                    Season::Winter => "vinter",
+                    Season::Spring => "vår",
+                    Season::Summer => "sommar",
+                    Season::Fall => "höst",
+                };
+                format!("varje {season}")
+            }
+            (Locale::English, Recurrence::EverySeason(s)) => format!("every {s}"),
+            (Locale::Swedish, Recurrence::Every(d)) => {
+                let (n, unit) = match d {
+                    Duration::Days(n) => (n, "dag"),
+                    Duration::Weeks(n) => (n, "vecka"),
+                    Duration::Months(n) => (n, "månad"),
+                    Duration::Years(n) => (n, "år"),
+                };
+                let every = if unit == "år" { "vart" } else { "var" };
+                // Andra och tjugoförsta, men elfte och tolfte
+                let ordinal = match (n % 10, n % 100) {
+                    (1 | 2, 11 | 12) => "e",
+                    (1 | 2, _) => "a",
+                    _ => "e",
+                };
+                match n {
+                    1 => format!("varje {unit}"),
+                    n => format!("{every} {n}:{ordinal} {unit}"),
+                }
+            }
+            (Locale::English, Recurrence::Every(d)) => match d {
+                Duration::Days(1) => "every day".to_string(),
+                Duration::Weeks(1) => "every week".to_string(),
+                Duration::Months(1) => "every month".to_string(),
+                Duration::Years(1) => "every year".to_string(),
+                d => format!("every {d}"),
+            },
+        }
+    }
+
+    fn approximated(&self, a: &Approximated, lookup: &impl Lookup) -> String {
+        a.constraints()
+            .map(|(c, b)| {
+                let word = self.constraint(&c);
+                let bound = match b {
+                    Bound::PoI(id) => self.name(&id, lookup),
+                    Bound::Dates(i) => self.interval(&i),
+                };
+                format!("{word} {bound}")
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    pub(crate) fn constraint(&self, c: &Constraint) -> &'static str {
+        match c {
+            Constraint::After => self.sv_en("efter", "after"),
+            Constraint::Before => self.sv_en("före", "before"),
+            Constraint::During => self.sv_en("under", "during"),
+        }
+    }
+
+    pub(crate) fn offset(&self, o: &Offset) -> String {
+        match (self.locale, o) {
+            (Locale::Swedish, Offset::After(d)) => format!("{} efter", self.duration(d)),
+            (Locale::Swedish, Offset::Before(d)) => format!("{} före", self.duration(d)),
+            (Locale::Swedish, Offset::SeasonBefore(s)) => format!("{} före", self.season(s)),
+            (Locale::Swedish, Offset::SeasonAfter(s)) => format!("{} efter", self.season(s)),
+            (Locale::English, o) => o.to_string(),
+        }
+    }
+
+    fn duration(&self, d: &Duration) -> String {
+        match self.locale {
+            Locale::English => d.to_string(),
+            Locale::Swedish => {
+                let (n, one, many) = match d {
+                    Duration::Days(n) => (n, "dag", "dagar"),
+                    Duration::Weeks(n) => (n, "vecka", "veckor"),
+                    Duration::Months(n) => (n, "månad", "månader"),
+                    Duration::Years(n) => (n, "år", "år"),
+                };
+                format!("{n} {}", if *n == 1 { one } else { many })
+            }
+        }
+    }
+
+    fn duration_ago(&self, d: &Duration) -> String {
+        match self.locale {
+            Locale::Swedish => format!("{} sedan", self.duration(d)),
+            Locale::English => format!("{} ago", self.duration(d)),
+        }
+    }
+
+    pub(crate) fn date(&self, d: &NaiveDate) -> String {
+        format!("{} {} {}", d.day(), self.month_of(d), d.year())
+    }
+
+    fn month_of(&self, d: &NaiveDate) -> &'static str {
+        self.month(&Month::of(d))
+    }
+
+    fn month(&self, m: &Month) -> &'static str {
+        match m {
+            Month::January => self.sv_en("januari", "January"),
+            Month::February => self.sv_en("februari", "February"),
+            Month::March => self.sv_en("mars", "March"),
+            Month::April => self.sv_en("april", "April"),
+            Month::May => self.sv_en("maj", "May"),
+            Month::June => self.sv_en("juni", "June"),
+            Month::July => self.sv_en("juli", "July"),
+            Month::August => self.sv_en("augusti", "August"),
+            Month::September => self.sv_en("september", "September"),
+            Month::October => self.sv_en("oktober", "October"),
+            Month::November => self.sv_en("november", "November"),
+            Month::December => self.sv_en("december", "December"),
+        }
+    }
+
+    fn season(&self, s: &Season) -> &'static str {
+        match s {
+            Season::Winter => self.sv_en("vintern", "winter"),
+            Season::Spring => self.sv_en("våren", "spring"),
+            Season::Summer => self.sv_en("sommaren", "summer"),
+            Season::Fall => self.sv_en("hösten", "fall"),
+        }
+    }
+
+    fn grade(&self, grade: u8) -> String {
+        match (self.locale, grade) {
+            (_, 0) => self.stadium(&Stadium::PreschoolClass).to_string(),
+            (Locale::Swedish, 10..=12) => format!("år {} på gymnasiet", grade - 9),
+            (Locale::Swedish, g) => format!("årskurs {g}"),
+            (Locale::English, 10..=12) => {
+                format!("year {} of upper secondary school", grade - 9)
+            }
+            (Locale::English, g) => format!("grade {g}"),
+        }
+    }
+
+    fn term(&self, term: &Term) -> &'static str {
+        match term {
+            Term::Fall => self.sv_en("höstterminen", "the fall term"),
+            Term::Spring => self.sv_en("vårterminen", "the spring term"),
+        }
+    }
+
+    fn stadium(&self, stadium: &Stadium) -> &'static str {
+        match stadium {
+            Stadium::Preschool => self.sv_en("förskolan", "preschool"),
+            Stadium::PreschoolClass => self.sv_en("förskoleklass", "preschool class"),
+            Stadium::Lower => self.sv_en("lågstadiet", "lower school"),
+            Stadium::Middle => self.sv_en("mellanstadiet", "middle school"),
+            Stadium::Upper => self.sv_en("högstadiet", "upper school"),
+            Stadium::UpperSecondary => self.sv_en("gymnasiet", "upper secondary school"),
+        }
+    }
+
+    pub(crate) fn name(&self, id: &Identifier, lookup: &impl Lookup) -> String {
+        lookup.poi(id).map_or_else(
+            || {
+                self.sv_en("en okänd händelse", "an unknown event")
+                    .to_string()
+            },
+            |p| p.name().get(),
+        )
+    }
+}
+
+/// Whole years from one date to another, negative when the second comes first
+fn full_years(from: NaiveDate, to: NaiveDate) -> i32 {
+    let years = to.year() - from.year();
+    if (to.month(), to.day()) < (from.month(), from.day()) {
+        years - 1
+    } else {
+        years
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SWEDISH: Style = Style {
+        locale: Locale::Swedish,
+        with_age: false,
+    };
+    const ENGLISH: Style = Style {
+        locale: Locale::English,
+        with_age: false,
+    };
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn writes_points_in_time_at_their_precision() {
+        let told = |style: &Style, p: PointInTime| style.point_in_time(&p, &());
+        let summer = PointInTime::Season(2013, Season::Summer);
+        assert_eq!(told(&SWEDISH, summer), "sommaren 2013");
+        assert_eq!(told(&ENGLISH, summer), "summer 2013");
+        let march = PointInTime::Month(2010, Month::March);
+        assert_eq!(told(&SWEDISH, march), "mars 2010");
+        let five = PointInTime::ChronologicalAge(5, Identifier::default());
+        assert_eq!(told(&SWEDISH, five), "vid 5 års ålder");
+        assert_eq!(told(&ENGLISH, five), "at age 5");
+        assert_eq!(told(&SWEDISH, PointInTime::Year(2008)), "2008");
+    }
+
+    #[test]
+    fn writes_time_ago_as_told_and_as_it_comes_out() {
+        let told = |style: &Style, p: PointInTime| style.point_in_time(&p, &());
+        let ago = PointInTime::ago(Duration::Years(3), date(2024, 3, 12));
+        assert_eq!(told(&SWEDISH, ago), "3 år före 12 mars 2024 (ca 2021)");
+        assert_eq!(
+            told(&ENGLISH, ago),
+            "3 years before 12 March 2024 (c. 2021)"
+        );
+        let ago = PointInTime::ago(Duration::Months(2), date(2024, 3, 12));
+        assert_eq!(
+            told(&SWEDISH, ago),
+            "2 månader före 12 mars 2024 (ca januari 2024)"
+        );
+    }
+
+    #[test]
+    fn says_why_a_date_cannot_be_placed_in_either_language() {
+        let e = TimeError::Contradiction(Constraint::After, Constraint::Before);
+        assert_eq!(
+            SWEDISH.error(&e, &()),
+            "Kan inte vara både efter och före det som angavs"
+        );
+        assert_eq!(
+            ENGLISH.error(&e, &()),
+            "Cannot be both after and before what was given"
+        );
+        let e = TimeError::InvalidWeek(2014, 53);
+        assert_eq!(SWEDISH.error(&e, &()), "2014 har ingen vecka 53");
+        assert_eq!(ENGLISH.error(&e, &()), "2014 has no week 53");
+        let e = ParseError::MissingYear("våren".to_string());
+        assert_eq!(
+            SWEDISH.parse_error(&e, &()),
+            "\"våren\" behöver ett år, t.ex. \"våren 2014\""
+        );
+        assert_eq!(
+            ENGLISH.parse_error(&e, &()),
+            "\"våren\" needs a year, e.g. \"våren 2014\""
+        );
+    }
+
+    #[test]
+    fn writes_intervals_as_briefly_as_they_allow() {
+        let years = Interval::new(date(2009, 1, 1), date(2014, 12, 31));
+        assert_eq!(SWEDISH.interval(&years), "2009–2014");
+        let months = Interval::new(date(2010, 3, 1), date(2010, 5, 31));
+        assert_eq!(SWEDISH.interval(&months), "mars–maj 2010");
+    }
+
+    #[test]
+    fn writes_how_often_it_recurs() {
+        let summers = Recurrence::EverySeason(Season::Summer);
+        assert_eq!(SWEDISH.recurrence(&summers), "varje sommar");
+        assert_eq!(ENGLISH.recurrence(&summers), "every summer");
+        let every = Recurrence::Every;
+        assert_eq!(
+            SWEDISH.recurrence(&every(Duration::Weeks(1))),
+            "varje vecka"
+        );
+        assert_eq!(
+            SWEDISH.recurrence(&every(Duration::Weeks(2))),
+            "var 2:a vecka"
+        );
+        assert_eq!(
+            SWEDISH.recurrence(&every(Duration::Years(3))),
+            "vart 3:e år"
+        );
+        assert_eq!(
+            SWEDISH.recurrence(&every(Duration::Days(11))),
+            "var 11:e dag"
+        );
+        assert_eq!(
+            ENGLISH.recurrence(&every(Duration::Months(1))),
+            "every month"
+        );
+    }
+
+    #[test]
+    fn writes_roughly_known_dates_as_roughly() {
+        let runtime = leptos::create_runtime();
+        let poi = PointOfInterest::new_bare_with_start(
+            Identifier::default(),
+            Identifier::default(),
+            "Flytten".to_string(),
+            PointInTime::Year(2008),
+        );
+        assert_eq!(SWEDISH.period(&poi, &()), "2008");
+        let uncertain = Calibration {
+            date: Certainty::Uncertain,
+            ..Default::default()
+        };
+        poi.set_calibration(Some(uncertain));
+        assert_eq!(SWEDISH.period(&poi, &()), "ca 2008");
+        assert_eq!(ENGLISH.period(&poi, &()), "around 2008");
+        // Med en felmarginal även när datumet i övrigt är troligt
+        let likely = |margin| Calibration {
+            margin: Some(margin),
+            ..Default::default()
+        };
+        poi.set_calibration(Some(likely(Duration::Years(1))));
+        assert_eq!(SWEDISH.period(&poi, &()), "ca 2008");
+        assert_eq!(ENGLISH.period(&poi, &()), "around 2008");
+        poi.set_calibration(Some(likely(Duration::Years(0))));
+        assert_eq!(SWEDISH.period(&poi, &()), "2008");
+        runtime.dispose();
+    }
+
+    #[test]
+    fn leaves_out_only_a_municipality_named_after_its_town() {
+        let place = |town: &str, municipality: &str| {
+            Place::new()
+                .in_town(town.to_string())
+                .in_municipality(municipality.to_string())
+                .in_country("Sverige".to_string())
+        };
+        assert_eq!(
+            SWEDISH.place(&place("Räveby", "Rävebys kommun")),
+            "Räveby, Sverige"
+        );
+        assert_eq!(
+            SWEDISH.place(&place("Åsele", "Åsele kommun")),
+            "Åsele, Sverige"
+        );
+        assert_eq!(
+            SWEDISH.place(&place("Ås", "Åsele kommun")),
+            "Ås, Åsele kommun, Sverige"
+        );
+    }
+}
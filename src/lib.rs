@@ -6,12 +6,18 @@ use leptos_router::*;
 // Modules
 mod components;
 mod pages;
+mod parse;
 mod types;
 mod data;
+mod solver;
+mod validation;
+mod format;
+mod export;
 mod viewstate;
 
 use crate::data::provide_timeline_context;
 // Top-Level pages
+use crate::pages::entity::EntityPage;
 use crate::pages::home::Home;
 use crate::pages::not_found::NotFound;
 
@@ -38,6 +44,7 @@ pub fn App() -> impl IntoView {
             <Router>
                 <Routes>
                     <Route path="/" view=Home/>
+                    <Route path="/entity/:id" view=EntityPage/>
                     <Route path="/*" view=NotFound/>
                 </Routes>
             </Router>
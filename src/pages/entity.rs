@@ -0,0 +1,84 @@
+use leptos::*;
+use leptos_router::*;
+
+use crate::{
+    data::{expect_timeline_context, TimelineContext},
+    types::{EntityKind, Identifier, Identify, LevelUp, Lookup, MyName},
+};
+
+/// One named entity, and every PoI in the timeline that mentions it
+#[component]
+pub fn EntityPage() -> impl IntoView {
+    let TimelineContext {
+        chronology,
+        cats,
+        style,
+        ..
+    } = expect_timeline_context();
+    let params = use_params_map();
+    let id = move || params.with(|p| p.get("id").and_then(|id| id.parse::<Identifier>().ok()));
+    let page = move || {
+        let style = style.get();
+        let Some(entity) = id().and_then(|id| chronology.entity(&id)) else {
+            let missing = style.sv_en("Det finns inget sådant namn", "No such entity");
+            return view! { <h1>{missing}</h1> }.into_view();
+        };
+        let id = entity.identity();
+        let kind = entity.kind();
+        let kinds = EntityKind::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, k)| {
+                view! {
+                    <option value=i.to_string() selected=*k == kind>
+                        {style.entity_kind(k)}
+                    </option>
+                }
+            })
+            .collect_view();
+        let set_kind = move |ev| {
+            let Some(kind) = event_target_value(&ev)
+                .parse::<usize>()
+                .ok()
+                .and_then(|i| EntityKind::ALL.get(i).copied())
+            else {
+                return;
+            };
+            chronology.entities.update(|e| {
+                if let Some(mut entity) = e.get(&id).cloned() {
+                    entity.set_kind(kind);
+                    e.insert(entity);
+                }
+            });
+        };
+        let mentioned = chronology
+            .mentioning(&id)
+            .into_iter()
+            .filter_map(|p| chronology.poi(&p))
+            .map(|p| {
+                let category = cats.with(|c| c.get(&p.parent()).map(|c| c.name().get()));
+                view! {
+                    <li>
+                        {p.name()} " "
+                        <span class="text-sm italic">{style.period(&p, &chronology)}</span> " "
+                        <span class="text-sm opacity-70">{category}</span>
+                    </li>
+                }
+            })
+            .collect_view();
+        view! {
+            <h1 class="text-xl font-bold">{entity.name()}</h1>
+            <select class="select select-xs" on:change=set_kind>
+                {kinds}
+            </select>
+            <ul>{mentioned}</ul>
+        }
+        .into_view()
+    };
+    view! {
+        <div class="min-h-[100svh] bg-sky-50 p-4">
+            <A href="/">{move || style.with(|s| s.sv_en("Tillbaka till tidslinjen", "Back to the timeline"))}</A>
+            {page}
+        </div>
+    }
+}
@@ -1,11 +1,10 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    marker::PhantomData,
-};
+use std::collections::{HashSet, VecDeque};
 
+use chrono::NaiveDate;
 use enum_dispatch::enum_dispatch;
 use leptos::{html::Div, logging::log, *};
 use leptos_hotkeys::{use_hotkeys, use_hotkeys_context, HotkeysContext};
+use leptos_router::A;
 use rand::seq::IteratorRandom;
 use uuid::Uuid;
 use wasm_bindgen::JsCast;
@@ -13,8 +12,16 @@ use web_sys::{Element, HtmlElement, ScrollToOptions};
 // use web_sys::ScrollIntoViewOptions;
 
 use crate::{
-    data::{expect_timeline_context, TimelineContext},
-    types::{HasBeginning, Identifier, Identify, MyName, NonSignalPointOfInterest},
+    data::{expect_timeline_context, ByMainCategory, TagFilter, TimelineContext},
+    export::as_text,
+    format::Locale,
+    parse::parse,
+    types::{
+        Calibration, Certainty, Claim, Coordinates, Duration, Ending, EntityKind, Hemisphere,
+        Identifier, Identify, LevelUp, Lookup, MyName, NamedEntity, Offset, Person, Place,
+        PointInTime, PointOfInterest, Reckoning, Recurrence, Reliability, Season, SeasonModel,
+        Sequence, Source, SourceKind, Tag, TimeError, WinterYear,
+    },
     viewstate::{expect_view_state, provide_view_state, ViewState},
 };
 
@@ -23,12 +30,9 @@ type Queue = RwSignal<VecDeque<Choice>>;
 #[component]
 pub fn Home() -> impl IntoView {
     let input_queue: Queue = create_rw_signal(VecDeque::new());
-    let (r_ground, _w_ground) = create_signal(String::from("Ground"));
-    let TimelineContext { add_poi, cats, .. } = expect_timeline_context();
-    let a_cat = cats.get_untracked().keys().next().unwrap().to_owned();
     let (search, search_w) = create_signal(false);
 
-    let call = Callback::new(move |s| log!("My mood is: {:?}", s));
+    let call = Callback::new(move |s: Mood| log!("My mood is: {} ({})", s.mood, s.comment));
     use_hotkeys!(("ctrl+m") => move |_| {
         input_queue
         .update(|v| {
@@ -40,6 +44,10 @@ pub fn Home() -> impl IntoView {
     use_hotkeys!(("ctrl+u") => move |_| {
         search_w(true);
     });
+    let (export, export_w) = create_signal(false);
+    use_hotkeys!(("ctrl+e") => move |_| {
+        export_w(true);
+    });
     provide_context(input_queue);
     view! {
         <div class="min-h-[100svh] bg-sky-50 grid">
@@ -47,7 +55,12 @@ pub fn Home() -> impl IntoView {
             <Show when=search>
                 <SearchPoi continue_search=search_w/>
             </Show>
+            <Show when=export>
+                <Export done=export_w/>
+            </Show>
             <Commands/>
+            <TagList/>
+            <Warnings/>
             <Modals/>
         </div>
     }
@@ -55,7 +68,12 @@ pub fn Home() -> impl IntoView {
 
 #[component]
 pub fn SearchPoi(continue_search: WriteSignal<bool>) -> impl IntoView {
-    let TimelineContext { pois, .. } = expect_timeline_context();
+    let TimelineContext {
+        pois,
+        chronology,
+        style,
+        ..
+    } = expect_timeline_context();
     let (term, term_w) = create_signal("".to_string());
     let autocomplete = Signal::derive(move || with!(|pois, term| pois.autocomplete(term)));
     let search = Signal::derive(move || with!(|pois, term| pois.search(term)));
@@ -63,15 +81,41 @@ pub fn SearchPoi(continue_search: WriteSignal<bool>) -> impl IntoView {
     let handle_input = move |t| term_w(event_target_value(&t));
     let search_results = Signal::derive(move || {
         search.with(|s| {
-            s.into_iter()
-                .map(|f| view! { <li>{f.to_string()}</li> })
+            s.iter()
+                .filter_map(|id| chronology.poi(id))
+                .map(|p| {
+                    let period = style.with(|s| s.period(&p, &chronology));
+                    view! {
+                        <li>
+                            {p.name()} <span class="text-sm italic">{period}</span>
+                        </li>
+                    }
+                })
                 .collect_view()
         })
     });
 
+    let entity_results = Signal::derive(move || {
+        let found = term.with(|t| chronology.entities.with(|e| e.search(t)));
+        found
+            .into_iter()
+            .filter_map(|id| chronology.entity(&id))
+            .map(|e| {
+                let kind = style.with(|s| s.entity_kind(&e.kind()));
+                view! {
+                    <li>
+                        <A href=format!("/entity/{}", e.identity().to_string())>{e.name()}</A>
+                        " "
+                        <span class="text-sm italic">{kind}</span>
+                    </li>
+                }
+            })
+            .collect_view()
+    });
+
     let auto_results = Signal::derive(move || {
         autocomplete.with(|s| {
-            s.into_iter()
+            s.iter()
                 .map(|f| view! { <li>{f.to_string()}</li> })
                 .collect_view()
         })
@@ -93,6 +137,9 @@ pub fn SearchPoi(continue_search: WriteSignal<bool>) -> impl IntoView {
                 <div>
                     <ol>{search_results}</ol>
                 </div>
+                <div>
+                    <ol>{entity_results}</ol>
+                </div>
                 <div>
                     <ol>{auto_results}</ol>
                 </div>
@@ -103,29 +150,668 @@ pub fn SearchPoi(continue_search: WriteSignal<bool>) -> impl IntoView {
 
 #[component]
 pub fn Commands() -> impl IntoView {
+    let TimelineContext {
+        add_expected_schooling,
+        style,
+        ..
+    } = expect_timeline_context();
+    use_hotkeys!(("ctrl+g") => move |_| add_expected_schooling(()));
     view! {
         <div class="absolute bottom-4 w-full">
             <ul class="flex gap-4">
                 <li>Gå</li>
                 <li>Se</li>
                 <li>Ny</li>
+                <li>
+                    <button class="btn btn-xs" on:click=move |_| add_expected_schooling(())>
+                        {move || match style.get().locale {
+                            Locale::Swedish => "Förväntad skolgång",
+                            Locale::English => "Expected schooling",
+                        }}
+                    </button>
+                </li>
+                <li>
+                    <StyleToggle/>
+                </li>
+                <li>
+                    <SeasonSettings/>
+                </li>
+                <li>
+                    <SchoolStart/>
+                </li>
+                <li>
+                    <CertaintyFilter/>
+                </li>
+                <li>
+                    <NewSource/>
+                    <AsOf/>
+                </li>
+                <li>
+                    <NewEntity/>
+                </li>
+                <li>
+                    <NewPlace/>
+                </li>
+                <li>
+                    <TagFilterControl/>
+                </li>
             </ul>
         </div>
     }
 }
 
+/// Switches the language dates are written in, and whether ages are shown
+#[component]
+pub fn StyleToggle() -> impl IntoView {
+    let TimelineContext { style, .. } = expect_timeline_context();
+    let toggle_locale = move |_| {
+        style.update(|s| {
+            s.locale = match s.locale {
+                Locale::Swedish => Locale::English,
+                Locale::English => Locale::Swedish,
+            }
+        })
+    };
+    let toggle_age = move |_| style.update(|s| s.with_age = !s.with_age);
+    let locale = move || match style.with(|s| s.locale) {
+        Locale::Swedish => "Svenska",
+        Locale::English => "English",
+    };
+    let age = move || match style.with(|s| (s.locale, s.with_age)) {
+        (Locale::Swedish, true) => "Visa inte ålder",
+        (Locale::Swedish, false) => "Visa ålder",
+        (Locale::English, true) => "Hide age",
+        (Locale::English, false) => "Show age",
+    };
+    view! {
+        <button class="btn btn-xs" on:click=toggle_locale>
+            {locale}
+        </button>
+        <button class="btn btn-xs" on:click=toggle_age>
+            {age}
+        </button>
+    }
+}
+
+/// Leaves out PoIs whose dates are less certain than chosen
+#[component]
+pub fn CertaintyFilter() -> impl IntoView {
+    let TimelineContext {
+        least_certain,
+        style,
+        ..
+    } = expect_timeline_context();
+    let next = move |_| least_certain.update(|l| *l = stricter(*l));
+    let label = move || match (style.with(|s| s.locale), least_certain.get()) {
+        (Locale::Swedish, None) => "Oavsett säkerhet",
+        (Locale::Swedish, Some(Certainty::Guess)) => "Gissat eller säkrare",
+        (Locale::Swedish, Some(Certainty::Uncertain)) => "Osäkert eller säkrare",
+        (Locale::Swedish, Some(Certainty::Likely)) => "Troligt eller säkrare",
+        (Locale::Swedish, Some(Certainty::Certain)) => "Bara säkert",
+        (Locale::English, None) => "Any certainty",
+        (Locale::English, Some(Certainty::Guess)) => "Guess or better",
+        (Locale::English, Some(Certainty::Uncertain)) => "Uncertain or better",
+        (Locale::English, Some(Certainty::Likely)) => "Likely or better",
+        (Locale::English, Some(Certainty::Certain)) => "Only certain",
+    };
+    view! {
+        <button class="btn btn-xs" on:click=next>
+            {label}
+        </button>
+    }
+}
+
+/// The next, stricter, step of the certainty filter, round to no filter at all
+fn stricter(least: Option<Certainty>) -> Option<Certainty> {
+    match least {
+        None => Some(Certainty::Guess),
+        Some(Certainty::Guess) => Some(Certainty::Uncertain),
+        Some(Certainty::Uncertain) => Some(Certainty::Likely),
+        Some(Certainty::Likely) => Some(Certainty::Certain),
+        Some(Certainty::Certain) => None,
+    }
+}
+
+/// Registers where what is known came from. An informant not met before is added
+/// by name.
+#[component]
+pub fn NewSource() -> impl IntoView {
+    let TimelineContext {
+        chronology, style, ..
+    } = expect_timeline_context();
+    let (kind, kind_w) = create_signal(SourceKind::default());
+    let (date, date_w) = create_signal(String::new());
+    let (informant, informant_w) = create_signal(String::new());
+    let (error, error_w) = create_signal::<Option<String>>(None);
+    let kinds = move || {
+        let style = style.get();
+        SourceKind::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, k)| view! { <option value=i>{style.source_kind(k)}</option> })
+            .collect_view()
+    };
+    let pick = move |ev| {
+        let picked = event_target_value(&ev).parse::<usize>().ok();
+        if let Some(k) = picked.and_then(|i| SourceKind::ALL.get(i)) {
+            kind_w(*k);
+        }
+    };
+    let people = move || {
+        chronology.people.with(|p| {
+            p.values()
+                .map(|p| view! { <option value=p.name().get()></option> })
+                .collect_view()
+        })
+    };
+    let add = move |_| {
+        let date = date.get_untracked();
+        let date = if date.trim().is_empty() {
+            PointInTime::Undetermined
+        } else {
+            match parse(&date, &chronology.parse_context(), &chronology) {
+                Ok(date) => date,
+                Err(e) => {
+                    error_w(Some(
+                        style.with_untracked(|s| s.parse_error(&e, &chronology)),
+                    ));
+                    return;
+                }
+            }
+        };
+        let mut source = Source::new(kind.get_untracked(), date);
+        let name = informant.get_untracked().trim().to_string();
+        if !name.is_empty() {
+            let known = chronology.people.with_untracked(|p| {
+                p.values()
+                    .find(|p| p.name().with_untracked(|n| *n == name))
+                    .map(|p| p.identity())
+            });
+            let informant = known.unwrap_or_else(|| {
+                let person = Person::new(name, PointInTime::Undetermined);
+                let id = person.identity();
+                chronology.people.update(|p| {
+                    p.insert(id, person);
+                });
+                id
+            });
+            source = source.told_by(informant);
+        }
+        chronology.sources.update(|s| {
+            s.insert(source.identity(), source);
+        });
+        date_w(String::new());
+        informant_w(String::new());
+        error_w(None);
+    };
+    let labels = move || match style.get().locale {
+        Locale::Swedish => ("Ny källa", "När", "Uppgiftslämnare", "Lägg till"),
+        Locale::English => ("New source", "When", "Informant", "Add"),
+    };
+    view! {
+        <details>
+            <summary>{move || labels().0}</summary>
+            <select class="select select-xs" on:change=pick>
+                {kinds}
+            </select>
+            <input
+                class="input input-xs w-32"
+                placeholder=move || labels().1
+                prop:value=date
+                on:input=move |e| date_w(event_target_value(&e))
+            />
+            <input
+                class="input input-xs w-24"
+                list="people"
+                placeholder=move || labels().2
+                prop:value=informant
+                on:input=move |e| informant_w(event_target_value(&e))
+            />
+            <datalist id="people">{people}</datalist>
+            <button class="btn btn-xs" on:click=add>
+                {move || labels().3}
+            </button>
+            {move || error().map(|e| view! { <p class="text-sm text-red-700">{e}</p> })}
+        </details>
+    }
+}
+
+/// Registers someone or something PoIs can mention
+#[component]
+pub fn NewEntity() -> impl IntoView {
+    let TimelineContext {
+        chronology, style, ..
+    } = expect_timeline_context();
+    let (name, name_w) = create_signal(String::new());
+    let (kind, kind_w) = create_signal(EntityKind::default());
+    let kinds = move || {
+        let style = style.get();
+        EntityKind::ALL
+            .iter()
+            .enumerate()
+            .map(|(i, k)| view! { <option value=i.to_string()>{style.entity_kind(k)}</option> })
+            .collect_view()
+    };
+    let pick = move |ev| {
+        let picked = event_target_value(&ev).parse::<usize>().ok();
+        if let Some(k) = picked.and_then(|i| EntityKind::ALL.get(i)) {
+            kind_w(*k);
+        }
+    };
+    let add = move |_| {
+        let name = name.get_untracked().trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        let entity = NamedEntity::new(name, kind.get_untracked());
+        chronology.entities.update(|e| e.insert(entity));
+        name_w(String::new());
+    };
+    let labels = move || match style.get().locale {
+        Locale::Swedish => ("Nytt namn", "Lägg till"),
+        Locale::English => ("New entity", "Add"),
+    };
+    view! {
+        <input
+            class="input input-xs"
+            placeholder=move || labels().0
+            prop:value=name
+            on:input=move |e| name_w(event_target_value(&e))
+        />
+        <select class="select select-xs" on:change=pick>
+            {kinds}
+        </select>
+        <button class="btn btn-xs" on:click=add>
+            {move || labels().1}
+        </button>
+    }
+}
+
+/// Registers somewhere PoIs can take place, at as many levels as are known
+#[component]
+pub fn NewPlace() -> impl IntoView {
+    let TimelineContext {
+        chronology, style, ..
+    } = expect_timeline_context();
+    let levels = [
+        ("Adress", "Address"),
+        ("Ort", "Town"),
+        ("Kommun", "Municipality"),
+        ("Land", "Country"),
+        ("Latitud", "Latitude"),
+        ("Longitud", "Longitude"),
+    ]
+    .map(|level| (level, create_rw_signal(String::new())));
+    let add = move |_| {
+        let [address, town, municipality, country, latitude, longitude] =
+            levels.map(|(_, text)| text.get_untracked().trim().to_string());
+        let mut place = Place::new();
+        if !address.is_empty() {
+            place = place.at_address(address);
+        }
+        if !town.is_empty() {
+            place = place.in_town(town);
+        }
+        if !municipality.is_empty() {
+            place = place.in_municipality(municipality);
+        }
+        if !country.is_empty() {
+            place = place.in_country(country);
+        }
+        if let (Ok(latitude), Ok(longitude)) = (latitude.parse(), longitude.parse()) {
+            place = place.located(Coordinates {
+                latitude,
+                longitude,
+            });
+        }
+        if place.levels().next().is_none() {
+            return;
+        }
+        chronology.places.update(|p| {
+            p.insert(place.identity(), place);
+        });
+        levels.iter().for_each(|(_, text)| text.set(String::new()));
+    };
+    let inputs = levels
+        .iter()
+        .map(|&((sv, en), text)| {
+            view! {
+                <input
+                    class="input input-xs w-24"
+                    placeholder=move || style.with(|s| s.sv_en(sv, en))
+                    prop:value=text
+                    on:input=move |e| text.set(event_target_value(&e))
+                />
+            }
+        })
+        .collect_view();
+    let labels = move || match style.get().locale {
+        Locale::Swedish => ("Ny plats", "Lägg till"),
+        Locale::English => ("New place", "Add"),
+    };
+    view! {
+        <details>
+            <summary>{move || labels().0}</summary>
+            {inputs}
+            <button class="btn btn-xs" on:click=add>
+                {move || labels().1}
+            </button>
+        </details>
+    }
+}
+
+/// Every tag in use, for tag inputs to suggest as they are typed
+#[component]
+pub fn TagList() -> impl IntoView {
+    let TimelineContext { chronology, .. } = expect_timeline_context();
+    let options = move || {
+        chronology
+            .tags()
+            .into_iter()
+            .map(|t| view! { <option value=t.to_string()></option> })
+            .collect_view()
+    };
+    view! { <datalist id="tags">{options}</datalist> }
+}
+
+/// Narrows the timeline to one tag and what is nested under it, by dimming or
+/// leaving out the rest
+#[component]
+pub fn TagFilterControl() -> impl IntoView {
+    let TimelineContext {
+        tag_filter, style, ..
+    } = expect_timeline_context();
+    let pick = move |ev| {
+        let tag = Tag::new(&event_target_value(&ev));
+        let hide = tag_filter.with_untracked(|f| f.as_ref().is_some_and(|f| f.hide));
+        tag_filter.set(tag.map(|tag| TagFilter { tag, hide }));
+    };
+    let toggle_hide = move |_| tag_filter.update(|f| f.iter_mut().for_each(|f| f.hide = !f.hide));
+    let filtering = move || tag_filter.with(|f| f.as_ref().map(|f| f.tag.to_string()));
+    let hiding = move || tag_filter.with(|f| f.as_ref().is_some_and(|f| f.hide));
+    let mode = move || match (style.with(|s| s.locale), hiding()) {
+        (Locale::Swedish, true) => "Döljer övriga",
+        (Locale::Swedish, false) => "Bleker övriga",
+        (Locale::English, true) => "Hiding others",
+        (Locale::English, false) => "Dimming others",
+    };
+    let placeholder = move || match style.with(|s| s.locale) {
+        Locale::Swedish => "Filtrera på tagg",
+        Locale::English => "Filter by tag",
+    };
+    view! {
+        <input
+            class="input input-xs w-32"
+            list="tags"
+            placeholder=placeholder
+            prop:value=move || filtering().unwrap_or_default()
+            on:change=pick
+        />
+        <button class="btn btn-xs" on:click=toggle_hide disabled=move || filtering().is_none()>
+            {mode}
+        </button>
+    }
+}
+
+/// Goes back to how the timeline looked by the end of an earlier session
+#[component]
+pub fn AsOf() -> impl IntoView {
+    let TimelineContext {
+        chronology, style, ..
+    } = expect_timeline_context();
+    let sessions = move || {
+        let style = style.get();
+        let as_of = style.sv_en("Som det var", "As of");
+        chronology
+            .sessions()
+            .into_iter()
+            .map(|d| {
+                let day = style.point_in_time(&PointInTime::Day(d), &chronology);
+                view! { <option value=d.to_string()>{format!("{as_of} {day}")}</option> }
+            })
+            .collect_view()
+    };
+    let pick = move |ev| {
+        let day = event_target_value(&ev).parse::<NaiveDate>().ok();
+        chronology
+            .as_of
+            .set(day.and_then(|d| d.and_hms_opt(23, 59, 59)));
+    };
+    let now = move || style.with(|s| s.sv_en("Som det är nu", "As of now"));
+    view! {
+        <select class="select select-xs" on:change=pick>
+            <option value="">{now}</option>
+            {sessions}
+        </select>
+    }
+}
+
+/// How the timeline counts its seasons
+#[component]
+pub fn SeasonSettings() -> impl IntoView {
+    let TimelineContext {
+        chronology, style, ..
+    } = expect_timeline_context();
+    let seasons = Signal::derive(move || chronology.timeline.with(|t| t.seasons()));
+    let change = move |f: fn(&mut SeasonModel)| {
+        chronology.timeline.update(|t| {
+            let mut seasons = t.seasons();
+            f(&mut seasons);
+            t.set_seasons(seasons);
+        })
+    };
+    let reckoning = move || match (style.get().locale, seasons.get().reckoning) {
+        (Locale::Swedish, Reckoning::Meteorological) => "Meteorologiska årstider",
+        (Locale::Swedish, Reckoning::Astronomical) => "Astronomiska årstider",
+        (Locale::English, Reckoning::Meteorological) => "Meteorological",
+        (Locale::English, Reckoning::Astronomical) => "Astronomical",
+    };
+    let hemisphere = move || match (style.get().locale, seasons.get().hemisphere) {
+        (Locale::Swedish, Hemisphere::Northern) => "Norra halvklotet",
+        (Locale::Swedish, Hemisphere::Southern) => "Södra halvklotet",
+        (Locale::English, Hemisphere::Northern) => "Northern hemisphere",
+        (Locale::English, Hemisphere::Southern) => "Southern hemisphere",
+    };
+    let winter_year = move || match (style.get().locale, seasons.get().winter_year) {
+        (Locale::Swedish, WinterYear::Starting) => "Vintern hör till året den börjar",
+        (Locale::Swedish, WinterYear::Ending) => "Vintern hör till året den slutar",
+        (Locale::English, WinterYear::Starting) => "Winter by the year it starts",
+        (Locale::English, WinterYear::Ending) => "Winter by the year it ends",
+    };
+    view! {
+        <button
+            class="btn btn-xs"
+            on:click=move |_| {
+                change(|s| {
+                    s.reckoning = match s.reckoning {
+                        Reckoning::Meteorological => Reckoning::Astronomical,
+                        Reckoning::Astronomical => Reckoning::Meteorological,
+                    }
+                })
+            }
+        >
+            {reckoning}
+        </button>
+        <button
+            class="btn btn-xs"
+            on:click=move |_| {
+                change(|s| {
+                    s.hemisphere = match s.hemisphere {
+                        Hemisphere::Northern => Hemisphere::Southern,
+                        Hemisphere::Southern => Hemisphere::Northern,
+                    }
+                })
+            }
+        >
+            {hemisphere}
+        </button>
+        <button
+            class="btn btn-xs"
+            on:click=move |_| {
+                change(|s| {
+                    s.winter_year = match s.winter_year {
+                        WinterYear::Starting => WinterYear::Ending,
+                        WinterYear::Ending => WinterYear::Starting,
+                    }
+                })
+            }
+        >
+            {winter_year}
+        </button>
+    }
+}
+
+/// Puts off or brings forward the year the timeline's person started school
+#[component]
+pub fn SchoolStart() -> impl IntoView {
+    let TimelineContext {
+        chronology, style, ..
+    } = expect_timeline_context();
+    let owner = move || chronology.timeline.with(|t| t.parent());
+    let years = move || chronology.person(&owner()).map_or(0, |p| p.school_start());
+    let set = move |ev| {
+        let Ok(years) = event_target_value(&ev).parse::<i8>() else {
+            return;
+        };
+        chronology.people.update(|p| {
+            if let Some(person) = p.get_mut(&owner()) {
+                person.set_school_start(years);
+            }
+        });
+    };
+    let label = move || match style.with(|s| s.locale) {
+        Locale::Swedish => "Skolstart, år senare",
+        Locale::English => "School start, years late",
+    };
+    view! {
+        <label class="text-xs">
+            {label}
+            <input
+                class="input input-xs w-14"
+                type="number"
+                min="-2"
+                max="3"
+                prop:value=years
+                on:change=set
+            />
+        </label>
+    }
+}
+
+/// The whole timeline as text, written the way dates are currently shown
+#[component]
+pub fn Export(done: WriteSignal<bool>) -> impl IntoView {
+    let TimelineContext {
+        cats,
+        chronology,
+        style,
+        ..
+    } = expect_timeline_context();
+    let text = move || cats.with(|c| style.with(|s| as_text(c, &chronology, s)));
+    let id = Uuid::new_v4();
+    view! {
+        <Portal>
+            <Dialog id>
+                <textarea readonly class="w-full h-96 font-mono text-sm" prop:value=text></textarea>
+                <button class="btn btn-sm" on:click=move |_| done(false)>
+                    {move || match style.get().locale {
+                        Locale::Swedish => "Stäng",
+                        Locale::English => "Close",
+                    }}
+                </button>
+            </Dialog>
+        </Portal>
+    }
+}
+
+/// Lists what cannot be right about the timeline, each leading to its PoI
+#[component]
+pub fn Warnings() -> impl IntoView {
+    let TimelineContext {
+        warnings,
+        chronology,
+        style,
+        ..
+    } = expect_timeline_context();
+    let items = move || {
+        warnings.with(|w| {
+            w.iter()
+                .map(|w| {
+                    let poi = w.poi();
+                    let said = style.with(|s| w.describe(s, &chronology));
+                    view! {
+                        <li>
+                            <button class="link" on:click=move |_| navigate_to_(&poi)>
+                                {said}
+                            </button>
+                        </li>
+                    }
+                })
+                .collect_view()
+        })
+    };
+    view! {
+        <Show when=move || warnings.with(|w| !w.is_empty())>
+            <details class="absolute top-4 right-4 bg-amber-50 border-2 border-amber-200 p-2 max-w-xs">
+                <summary class="text-amber-800">
+                    {move || {
+                        let title = match style.get().locale {
+                            Locale::Swedish => "Varningar",
+                            Locale::English => "Warnings",
+                        };
+                        format!("{title} ({})", warnings.with(|w| w.len()))
+                    }}
+                </summary>
+                <ul class="text-sm">{items}</ul>
+            </details>
+        </Show>
+    }
+}
+
 #[component]
 pub fn Timeline() -> impl IntoView {
     let TimelineContext {
-        cats, span, pois, ..
+        cats,
+        pois,
+        chronology,
+        tag_filter,
+        begins,
+        least_certain,
+        ..
     } = expect_timeline_context();
+    let certain_enough = move |id: &Identifier| {
+        chronology
+            .poi(id)
+            .is_none_or(|p| p.is_certain_enough(least_certain.get()))
+    };
+    // Taggfiltret kan dölja det som inte matchar, annars blekas det bara
+    let shown = move |id: &Identifier| {
+        tag_filter.with(|f| {
+            f.as_ref()
+                .filter(|f| f.hide)
+                .zip(chronology.poi(id))
+                .is_none_or(|(f, p)| f.lets_through(&p))
+        })
+    };
     let timeline_ref = create_node_ref::<Div>();
     let ViewState {
-        resolution,
         day_height,
         timeline_height,
-        cursor,
     } = provide_view_state(timeline_ref);
+    // Gå till en dag, skriven som den berättas
+    let queue = expect_context::<Queue>();
+    use_hotkeys!(("alt+g") => move |_| {
+        let id = Uuid::new_v4();
+        let go_to = Callback::new(move |when: PointInTime| {
+            let at = when.resolve(&chronology).ok().zip(begins.get_untracked());
+            if let Some((tl, (i, b))) = timeline_ref.get_untracked().zip(at) {
+                let top = (i.earliest() - b).num_days() as f64 * day_height.get_untracked();
+                tl.set_scroll_top(top as i32);
+            }
+            queue.update(|q| q.retain(|c| c.identify() != id));
+        });
+        queue.update(|q| q.push_front(Choice::from(TypedDate { id, callback: go_to })));
+    });
     let (current, current_w) = create_signal::<Identifier>(Identifier::default());
     let day_css_var = Signal::derive(move || day_height.with(|h| format!("{h}px")));
     let update_current = move || {
@@ -135,12 +821,11 @@ pub fn Timeline() -> impl IntoView {
                 let mut rng = rand::thread_rng();
                 p.keys().choose(&mut rng)
             })
-            .clone()
             .unwrap(),
         );
     };
     use_hotkeys!(("ctrl+y") => move |_| {
-        let maybe_el = document().get_element_by_id(&format!("poi-{}", current().to_string()));
+        let maybe_el = document().get_element_by_id(&format!("poi-{}", current()));
         let maybe_con = document().get_element_by_id("timeline-container");
         if let Some((el, con)) = maybe_el.zip(maybe_con) {
 
@@ -167,10 +852,23 @@ pub fn Timeline() -> impl IntoView {
                 class="flex gap-24"
                 style:height=move || format!("{}px", timeline_height.get())
             >
-                <For each=move || cats.get().into_iter() key=move |(u, _)| u.clone() let:ca>
+                <For each=move || cats.get().into_iter() key=move |(u, _)| *u let:ca>
                     <div id="röd" class="border-2 border-red-200 w-[300px] h-full relative">
-                        <h2 class="text-blue-600 sticky top-1">{ca.1.name()}</h2>
-                        <For each=move || ca.1.pois() key=move |u| u.clone() let:poi_id>
+                        <h2 class="text-blue-600 sticky top-1">
+                            {ca.1.name()} <SequenceToggle sequence=ca.1.sequence()/>
+                        </h2>
+                        <Residences category=ca.1.clone()/>
+                        <For
+                            each=move || {
+                                chronology
+                                    .chronological(ca.1.pois())
+                                    .into_iter()
+                                    .filter(|id| certain_enough(id) && shown(id))
+                                    .collect::<Vec<_>>()
+                            }
+                            key=move |u| *u
+                            let:poi_id
+                        >
                             <Point id=poi_id/>
                         </For>
                     </div>
@@ -180,8 +878,65 @@ pub fn Timeline() -> impl IntoView {
     }
 }
 
+/// How many moves, and how long in each place, for categories whose PoIs have places
+#[component]
+pub fn Residences(category: ByMainCategory) -> impl IntoView {
+    let TimelineContext {
+        chronology, style, ..
+    } = expect_timeline_context();
+    let summary = move || {
+        let residences = chronology.residences(category.pois());
+        if residences.stays.is_empty() {
+            return None;
+        }
+        let style = style.get();
+        let moves = match (style.locale, residences.moves) {
+            (Locale::Swedish, 1) => "1 flytt".to_string(),
+            (Locale::Swedish, n) => format!("{n} flyttar"),
+            (Locale::English, 1) => "1 move".to_string(),
+            (Locale::English, n) => format!("{n} moves"),
+        };
+        let stays = residences
+            .stays
+            .iter()
+            .filter_map(|(place, stayed)| {
+                let place = chronology.place(place)?;
+                Some(format!("{} {}", place.name().get(), style.length(stayed)))
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        Some(view! { <p class="text-sm text-blue-600 sticky top-8">{moves} ": " {stays}</p> })
+    };
+    summary
+}
+
+/// Switches a category between overlapping PoIs and one at a time
+#[component]
+pub fn SequenceToggle(sequence: RwSignal<Sequence>) -> impl IntoView {
+    let TimelineContext { style, .. } = expect_timeline_context();
+    let toggle = move |_| {
+        sequence.update(|s| {
+            *s = match s {
+                Sequence::Overlapping => Sequence::Exclusive,
+                Sequence::Exclusive => Sequence::Overlapping,
+            }
+        })
+    };
+    let label = move || match (style.get().locale, sequence.get()) {
+        (Locale::Swedish, Sequence::Overlapping) => "Överlappande",
+        (Locale::Swedish, Sequence::Exclusive) => "En i taget",
+        (Locale::English, Sequence::Overlapping) => "Overlapping",
+        (Locale::English, Sequence::Exclusive) => "One at a time",
+    };
+    view! {
+        <button class="btn btn-xs" on:click=toggle>
+            {label}
+        </button>
+    }
+}
+
 fn navigate_to_(poi: &Identifier) {
-    let maybe_el = document().get_element_by_id(&format!("poi-{}", poi.to_string()));
+    let maybe_el = document().get_element_by_id(&format!("poi-{}", poi));
     let maybe_con = document().get_element_by_id("timeline-container");
     if let Some((el, con)) = maybe_el.zip(maybe_con) {
         log!("Scroll! {:?}", el.text_content());
@@ -192,7 +947,6 @@ fn center_element_in_container(el: Element, con: Element) {
     let el = el.dyn_into::<HtmlElement>().unwrap();
     let con = con.dyn_into::<HtmlElement>().unwrap();
 
-    let poi = el.inner_text();
     let offset_top = el.offset_top();
     let con_height = con.offset_height();
     let scroll_y = offset_top - (con_height / 2);
@@ -217,33 +971,856 @@ fn center_element_in_container(el: Element, con: Element) {
     //     scroll_h
     // );
 
-    let mut scroll_opts = ScrollToOptions::new();
-    scroll_opts.top(scroll_y as f64);
-    scroll_opts.left(scroll_h as f64);
-    scroll_opts.behavior(web_sys::ScrollBehavior::Smooth);
+    let scroll_opts = ScrollToOptions::new();
+    scroll_opts.set_top(scroll_y as f64);
+    scroll_opts.set_left(scroll_h as f64);
+    scroll_opts.set_behavior(web_sys::ScrollBehavior::Smooth);
     con.scroll_to_with_scroll_to_options(&scroll_opts);
 }
 
 #[component]
 pub fn Point(id: Identifier) -> impl IntoView {
-    let TimelineContext { pois, begins, .. } = expect_timeline_context();
+    let TimelineContext {
+        pois,
+        chronology,
+        solution,
+        inferred_ends,
+        begins,
+        style,
+        tag_filter,
+        ..
+    } = expect_timeline_context();
     let ViewState { day_height, .. } = expect_view_state();
-    let poi = pois.with_untracked(|p| p.get(&id).copied().unwrap());
-    let origin_distance = Signal::derive(move || begins.with(|b| poi.begins() - b.unwrap()));
+    // Kan ha tagits bort sedan listan ritades
+    let Some(poi) = pois.with_untracked(|p| p.get(&id).copied()) else {
+        return ().into_view();
+    };
+    // Det som kan sägas utifrån hela tidslinjen går före det PoI:n själv anger
+    let within = Signal::derive(move || {
+        solution
+            .with(|s| s.narrowed.get(&id).copied())
+            .map_or_else(|| poi.starts_within(&chronology), Ok)
+    });
+    let origin_distance = Signal::derive(move || {
+        with!(|within, begins| within.ok().zip(*begins).map(|(w, b)| w.earliest() - b))
+    });
     let pixel_top = Signal::derive(move || {
-        let px =
-            with!(|origin_distance, day_height| origin_distance.num_days() as f64 * day_height);
+        let px = with!(|origin_distance, day_height| {
+            origin_distance.map_or(0.0, |d| d.num_days() as f64 * day_height)
+        });
         format!("{px}px")
     });
-    let id = format!("poi-{}", poi.identity().to_string());
+    // Osäkra datum ritas ut som ett spann över hela den möjliga perioden
+    let fuzzy = Signal::derive(move || within.with(|w| w.map_or(true, |w| !w.is_exact())));
+    let inferred = Signal::derive(move || inferred_ends.with(|i| i.get(&id).copied()));
+    let ends = Signal::derive(move || match poi.ends_within(&chronology) {
+        Err(TimeError::Undetermined) => inferred
+            .get()
+            .and_then(|next| Ending::Upon(next).resolve(&poi, &chronology).ok()),
+        ends => ends.ok(),
+    });
+    // Från tidigaste möjliga början till senaste möjliga slut
+    let pixel_height = Signal::derive(move || {
+        with!(|within, ends, day_height| {
+            let starts = within.ok()?;
+            let covers = ends.map_or(starts, |e| starts.hull(&e));
+            (!covers.is_exact()).then(|| format!("{}px", covers.days() as f64 * day_height))
+        })
+    });
+    let fuzzy_end =
+        Signal::derive(move || ends.with(|e| e.filter(|e| !e.is_exact()).map(|_| "dashed")));
+    // Relativa datum visas som de angavs, tillsammans med perioden de blir
+    let told = move || {
+        let style = style.get();
+        let period = style.period(&poi, &chronology);
+        let dates = poi
+            .starts_as_of(chronology.as_of())
+            .is_relative()
+            .then(|| within.get().ok())
+            .flatten()
+            .map(|w| format!(" ({})", style.interval(&w)))
+            .unwrap_or_default();
+        view! { <p class="text-sm italic">{period + &dates}</p> }
+    };
+    // Ett slut som bara antas visas som ett förslag, som kan bekräftas eller avfärdas
+    let inference = move || {
+        inferred.get().map(|next| {
+            let style = style.get();
+            let until = style.ending(&Ending::Upon(next), &chronology);
+            let (inferred, confirm, keep_open) = match style.locale {
+                Locale::Swedish => ("antaget", "Bekräfta", "Låt vara öppen"),
+                Locale::English => ("inferred", "Confirm", "Keep open"),
+            };
+            view! {
+                <p class="text-sm italic opacity-70">
+                    {format!("{} ({inferred})", until.unwrap_or_default())}
+                    <button class="btn btn-xs" on:click=move |_| poi.set_ends(Ending::Upon(next))>
+                        {confirm}
+                    </button>
+                    <button class="btn btn-xs" on:click=move |_| poi.set_ends(Ending::Ongoing)>
+                        {keep_open}
+                    </button>
+                </p>
+            }
+        })
+    };
+    let problem = move || {
+        within.with(|w| {
+            w.err().map(|e| {
+                let said = style.with(|s| s.error(&e, &chronology));
+                view! { <p class="text-sm text-red-700">{said}</p> }
+            })
+        })
+    };
+    let conflicts = move || {
+        solution.with(|s| {
+            s.conflicts_for(&id)
+                .into_iter()
+                .map(|c| {
+                    let style = style.get();
+                    let said = c
+                        .iter()
+                        .map(|r| r.describe(&style, &chronology))
+                        .collect::<Vec<_>>()
+                        .join("; ");
+                    let conflicts = match style.locale {
+                        Locale::Swedish => "Motsägs: ",
+                        Locale::English => "Conflicts: ",
+                    };
+                    view! { <p class="text-sm text-red-700">{conflicts} {said}</p> }
+                })
+                .collect_view()
+        })
+    };
+    // Det som bara förväntas kan bekräftas som det är, eller justeras till det som faktiskt hände
+    let (adjusting, adjusting_w) = create_signal::<Option<Choice>>(None);
+    let adjust = Callback::new(move |text: String| {
+        if let Ok(starts) = parse(&text, &chronology.parse_context(), &chronology) {
+            poi.set_starts(starts);
+            poi.confirm();
+            adjusting_w(None);
+        }
+    });
+    let tentative = Signal::derive(move || poi.is_tentative());
+    // Ju osäkrare, desto blekare
+    let filtered_out =
+        move || tag_filter.with(|f| f.as_ref().is_some_and(|f| !f.lets_through(&poi)));
+    let faintness = move || {
+        opacity(
+            poi.calibration().map(|c| c.weakest()),
+            tentative(),
+            filtered_out(),
+        )
+    };
+    let expected = move || {
+        tentative().then(|| {
+            let (expected, confirm, adjust_label) = match style.with(|s| s.locale) {
+                Locale::Swedish => ("Förväntat", "Bekräfta", "Justera"),
+                Locale::English => ("Expected", "Confirm", "Adjust"),
+            };
+            view! {
+                <p class="text-sm italic opacity-70">
+                    {expected}
+                    <button class="btn btn-xs" on:click=move |_| poi.confirm()>
+                        {confirm}
+                    </button>
+                    <button
+                        class="btn btn-xs"
+                        on:click=move |_| {
+                            adjusting_w(Some(Choice::from(UserMayChoose::<String>::new(adjust))))
+                        }
+                    >
+                        {adjust_label}
+                    </button>
+                </p>
+            }
+        })
+    };
+    // Taggarna filtrerar tidslinjen när man klickar på dem
+    let tags = move || {
+        poi.tags()
+            .into_iter()
+            .map(|tag| {
+                let filter = tag.clone();
+                let filter_by = move |_| {
+                    let hide = tag_filter.with_untracked(|f| f.as_ref().is_some_and(|f| f.hide));
+                    tag_filter.set(Some(TagFilter {
+                        tag: filter.clone(),
+                        hide,
+                    }));
+                };
+                view! {
+                    <button class="badge badge-primary badge-outline" on:click=filter_by>
+                        {tag.to_string()}
+                    </button>
+                }
+            })
+            .collect_view()
+    };
+    // Återkommande händelser ritas ut var för sig, räknat från periodens början
+    let occurrences = move || {
+        poi.recurs()?;
+        let starts = within.get().ok()?.earliest();
+        let day_height = day_height.get();
+        let marks = poi
+            .occurrences(&chronology)
+            .ok()?
+            .take(MAX_OCCURRENCES)
+            .map(|o| {
+                let top = (o.earliest() - starts).num_days() as f64 * day_height;
+                let height = (o.days() as f64 * day_height).max(2.0);
+                view! {
+                    <div
+                        class="absolute w-full bg-blue-800/30"
+                        style:top=format!("{top}px")
+                        style:height=format!("{height}px")
+                    ></div>
+                }
+            })
+            .collect_view();
+        Some(marks)
+    };
+    // Går källorna isär syns det på raden, versionerna finns bland detaljerna
+    let disputed = Signal::derive(move || poi.is_disputed(&chronology));
+    let disagree = move || {
+        disputed().then(|| {
+            let disagree = style.with(|s| s.sv_en("Uppgifterna går isär", "Accounts disagree"));
+            view! { <p class="text-sm text-amber-700">{disagree}</p> }
+        })
+    };
+    // Det som ändrar PoI:n öppnas för sig, så att raden bara visar vad den är
+    let (editing, editing_w) = create_signal(false);
+    let close = Callback::new(move |_| editing_w(false));
+    let id = format!("poi-{}", poi.identity());
+
+    view! {
+        <Portal>{move || adjusting().map(|c| c.proffer())}</Portal>
+        <Portal>{move || editing().then(|| view! { <PointDetails poi close/> })}</Portal>
+        <div
+            id=id
+            // Allt i en och samma klass, annars skriver den över de enskilda
+            class=move || {
+                let border = if disputed() { "border-amber-600" } else { "border-blue-800" };
+                let fuzzy = if fuzzy() { "border-dashed bg-blue-100/50" } else { "" };
+                format!("border-2 absolute w-full {border} {fuzzy} {}", faintness())
+            }
+            style:top=pixel_top
+            style:height=pixel_height
+            style:border-bottom-style=fuzzy_end
+        >
+            {occurrences}
+            <button class="link font-bold" on:click=move |_| editing_w.update(|e| *e = !*e)>
+                {poi.name()}
+            </button>
+            {told}
+            {tags}
+            {disagree}
+            {expected}
+            {inference}
+            {problem}
+            {conflicts}
+        </div>
+    }
+    .into_view()
+}
+
+/// Everything that can be changed about a PoI, apart from its row in the
+/// timeline
+#[component]
+pub fn PointDetails(poi: PointOfInterest, close: Callback<()>) -> impl IntoView {
+    let TimelineContext {
+        chronology, style, ..
+    } = expect_timeline_context();
+    let calibration = move || {
+        poi.calibration().map(|c| {
+            let told = style.with(|s| s.calibration(&c));
+            view! { <p class="text-sm opacity-70">{told}</p> }
+        })
+    };
+    // Källorna kan öppnas var för sig, och fler kan läggas till
+    let (showing, showing_w) = create_signal::<Option<Identifier>>(None);
+    let sources = move || {
+        let style = style.get();
+        let (uncite, add) = match style.locale {
+            Locale::Swedish => ("Ta bort", "Lägg till källa"),
+            Locale::English => ("Remove", "Add source"),
+        };
+        let cited = poi
+            .sources()
+            .into_iter()
+            .filter_map(|s| chronology.source(&s))
+            .map(|s| {
+                let source = s.identity();
+                let toggle =
+                    move |_| showing_w.update(|o| *o = (*o != Some(source)).then_some(source));
+                view! {
+                    <li class="text-sm">
+                        <button class="link" on:click=toggle>
+                            {style.source(&s, &chronology)}
+                        </button>
+                        <button class="btn btn-xs" on:click=move |_| poi.uncite(&source)>
+                            {uncite}
+                        </button>
+                    </li>
+                }
+            })
+            .collect_view();
+        let uncited = chronology.sources.with(|s| {
+            s.values()
+                .filter(|s| !poi.cites(&s.identity()))
+                .map(|s| {
+                    view! {
+                        <option value=s.identity().to_string()>
+                            {style.source(s, &chronology)}
+                        </option>
+                    }
+                })
+                .collect_view()
+        });
+        let cite = move |ev| {
+            let picked = event_target_value(&ev);
+            let source = chronology
+                .sources
+                .with_untracked(|s| s.keys().find(|k| k.to_string() == picked).copied());
+            if let Some(source) = source {
+                poi.cite(source);
+            }
+        };
+        view! {
+            <ul>{cited}</ul>
+            <select class="select select-xs" on:change=cite prop:value="">
+                <option value="">{add}</option>
+                {uncited}
+            </select>
+            {move || showing().map(|id| view! { <SourceDetails id/> })}
+        }
+    };
+    // Alla källors versioner, och vilken som gäller kan bytas
+    let claims = move || {
+        let claims = poi.claims();
+        (claims.len() > 1).then(|| {
+            let style = style.get();
+            let (preferred, prefer) = match style.locale {
+                Locale::Swedish => ("föredragen", "Föredra"),
+                Locale::English => ("preferred", "Prefer"),
+            };
+            claims
+                .into_iter()
+                .map(|c| {
+                    let told = format!(
+                        "{} ({})",
+                        style.point_in_time(&c.starts, &chronology),
+                        chronology
+                            .source(&c.source)
+                            .map(|s| style.source(&s, &chronology))
+                            .unwrap_or_default()
+                    );
+                    let choice = if poi.preferred() == Some(c.source) {
+                        view! { <span class="italic">{preferred}</span> }.into_view()
+                    } else {
+                        view! {
+                            <button class="btn btn-xs" on:click=move |_| poi.prefer(&c.source)>
+                                {prefer}
+                            </button>
+                        }
+                        .into_view()
+                    };
+                    view! { <li class="text-sm">{told} " " {choice}</li> }
+                })
+                .collect_view()
+        })
+    };
+    // Var det hände, med alla nivåer som är kända
+    let place = move || {
+        let style = style.get();
+        let at = poi
+            .place()
+            .and_then(|p| chronology.place(&p))
+            .map(|p| style.place(&p));
+        let places = chronology.places.with(|p| {
+            p.values()
+                .map(|p| {
+                    view! {
+                        <option value=p.identity().to_string() selected=poi.place() == Some(p.identity())>
+                            {style.place(p)}
+                        </option>
+                    }
+                })
+                .collect_view()
+        });
+        let pick = move |ev| {
+            poi.set_place(event_target_value(&ev).parse::<Identifier>().ok());
+        };
+        let nowhere = match style.locale {
+            Locale::Swedish => "Ingen plats",
+            Locale::English => "No place",
+        };
+        view! {
+            <p class="text-sm">{at}</p>
+            <select class="select select-xs" on:change=pick>
+                <option value="" selected=poi.place().is_none()>{nowhere}</option>
+                {places}
+            </select>
+        }
+    };
+    // Taggar läggs till som de skrivs, med de befintliga som förslag
+    let tags = move || {
+        let chips = poi
+            .tags()
+            .into_iter()
+            .map(|tag| {
+                let removed = tag.clone();
+                view! {
+                    <span class="badge badge-primary badge-outline gap-1">
+                        {tag.to_string()}
+                        <button on:click=move |_| poi.untag(&removed)>"×"</button>
+                    </span>
+                }
+            })
+            .collect_view();
+        let add = move |ev| {
+            let input = event_target::<web_sys::HtmlInputElement>(&ev);
+            if let Some(tag) = Tag::new(&input.value()) {
+                poi.tag(tag);
+            }
+            input.set_value("");
+        };
+        let placeholder = style.with(|s| match s.locale {
+            Locale::Swedish => "Tagga",
+            Locale::English => "Tag",
+        });
+        view! {
+            <div class="flex flex-wrap gap-1 text-sm">
+                {chips}
+                <input class="input input-xs w-24" list="tags" placeholder=placeholder on:change=add/>
+            </div>
+        }
+    };
+    // De som nämns leder till sin egen sida
+    let entities = move || {
+        let style = style.get();
+        let chips = poi
+            .entities()
+            .into_iter()
+            .filter_map(|e| chronology.entity(&e))
+            .map(|e| {
+                let entity = e.identity();
+                view! {
+                    <span class="badge badge-outline gap-1">
+                        <A href=format!("/entity/{}", entity.to_string())>{e.name()}</A>
+                        <button on:click=move |_| poi.unmention(&entity)>"×"</button>
+                    </span>
+                }
+            })
+            .collect_view();
+        let unmentioned = chronology.entities.with(|e| {
+            e.values()
+                .filter(|e| !poi.mentions(&e.identity()))
+                .map(|e| {
+                    view! {
+                        <option value=e.identity().to_string()>
+                            {e.name()} " (" {style.entity_kind(&e.kind())} ")"
+                        </option>
+                    }
+                })
+                .collect_view()
+        });
+        let mention = move |ev| {
+            if let Ok(entity) = event_target_value(&ev).parse::<Identifier>() {
+                poi.mention(entity);
+            }
+        };
+        let add = match style.locale {
+            Locale::Swedish => "Nämn",
+            Locale::English => "Mention",
+        };
+        view! {
+            <div class="flex flex-wrap gap-1 text-sm">
+                {chips}
+                <select class="select select-xs" on:change=mention prop:value="">
+                    <option value="">{add}</option>
+                    {unmentioned}
+                </select>
+            </div>
+        }
+    };
+    let close_label = move || match style.get().locale {
+        Locale::Swedish => "Stäng",
+        Locale::English => "Close",
+    };
+
+    view! {
+        <div class="fixed right-4 top-16 z-20 w-96 flex flex-col gap-2 p-2 bg-white border-2 border-blue-800 shadow">
+            <h3 class="font-bold">{poi.name()}</h3>
+            <AnchorChoice poi/>
+            <RecurrenceChoice poi/>
+            {calibration}
+            <CalibrationChoice poi/>
+            <ul>{claims}</ul>
+            <NewClaim poi/>
+            {place}
+            {tags}
+            {entities}
+            {sources}
+            <button class="btn btn-xs self-end" on:click=move |_| close(())>
+                {close_label}
+            </button>
+        </div>
+    }
+}
 
+/// Adds a source's version of when a PoI started
+#[component]
+pub fn NewClaim(poi: PointOfInterest) -> impl IntoView {
+    let TimelineContext {
+        chronology, style, ..
+    } = expect_timeline_context();
+    let (source, source_w) = create_signal::<Option<Identifier>>(None);
+    let (starts, starts_w) = create_signal(String::new());
+    let (error, error_w) = create_signal::<Option<String>>(None);
+    let sources = move || {
+        let style = style.get();
+        chronology.sources.with(|s| {
+            s.values()
+                .map(|s| {
+                    view! {
+                        <option value=s.identity().to_string()>
+                            {style.source(s, &chronology)}
+                        </option>
+                    }
+                })
+                .collect_view()
+        })
+    };
+    let pick = move |ev| {
+        let picked = event_target_value(&ev);
+        let source = chronology
+            .sources
+            .with_untracked(|s| s.keys().find(|k| k.to_string() == picked).copied());
+        source_w(source);
+    };
+    let labels = move || match style.with(|s| s.locale) {
+        Locale::Swedish => ("Enligt källa", "När", "Lägg till uppgift"),
+        Locale::English => ("According to", "When", "Add account"),
+    };
+    let add_claim = move |_| {
+        let Some(source) = source.get_untracked() else {
+            return;
+        };
+        let starts = starts.get_untracked();
+        match parse(&starts, &chronology.parse_context(), &chronology) {
+            Ok(starts) => {
+                poi.claim(Claim { source, starts });
+                starts_w(String::new());
+                error_w(None);
+            }
+            Err(e) => error_w(Some(
+                style.with_untracked(|s| s.parse_error(&e, &chronology)),
+            )),
+        }
+    };
     view! {
-        <div id=id class="border-2 relative border-blue-800" style:top=pixel_top>
-            {poi.name()}
+        <div class="flex flex-wrap gap-1 text-sm">
+            <select class="select select-xs" on:change=pick>
+                <option value="">{move || labels().0}</option>
+                {sources}
+            </select>
+            <input
+                class="input input-xs w-32"
+                placeholder=move || labels().1
+                prop:value=starts
+                on:input=move |e| starts_w(event_target_value(&e))
+            />
+            <button class="btn btn-xs" on:click=add_claim disabled=move || source().is_none()>
+                {move || labels().2}
+            </button>
+            {move || error().map(|e| view! { <p class="text-red-700">{e}</p> })}
         </div>
     }
 }
 
+/// Dates a PoI from the start of another one, e.g. two years after a move
+#[component]
+pub fn AnchorChoice(poi: PointOfInterest) -> impl IntoView {
+    let TimelineContext { pois, style, .. } = expect_timeline_context();
+    let (years, years_w) = create_signal(0u32);
+    let (before, before_w) = create_signal(false);
+    let anchored_to = move || match poi.starts() {
+        PointInTime::RelativeTo(anchor, _) => Some(anchor),
+        _ => None,
+    };
+    // Ändras avståndet flyttas den med, om den redan är förankrad
+    let anchor_to = move |anchor: Identifier| {
+        let years = Duration::Years(years.get_untracked());
+        let offset = if before.get_untracked() {
+            Offset::Before(years)
+        } else {
+            Offset::After(years)
+        };
+        poi.set_starts(PointInTime::RelativeTo(anchor, offset));
+    };
+    let pick = move |ev| {
+        let picked = event_target_value(&ev);
+        let anchor = pois.with_untracked(|p| p.keys().find(|k| k.to_string() == picked));
+        if let Some(anchor) = anchor {
+            anchor_to(anchor);
+        }
+    };
+    let others = move || {
+        pois.with(|p| {
+            p.keys()
+                .filter(|k| *k != poi.identity())
+                .filter_map(|k| p.get(&k).copied())
+                .map(|p| {
+                    view! {
+                        <option
+                            value=p.identity().to_string()
+                            selected=move || anchored_to() == Some(p.identity())
+                        >
+                            {p.name()}
+                        </option>
+                    }
+                })
+                .collect_view()
+        })
+    };
+    let set_years = move |ev| {
+        years_w(event_target_value(&ev).parse().unwrap_or_default());
+        if let Some(a) = anchored_to() {
+            anchor_to(a);
+        }
+    };
+    let set_before = move |ev| {
+        before_w(event_target_value(&ev) == "before");
+        if let Some(a) = anchored_to() {
+            anchor_to(a);
+        }
+    };
+
+    let labels = move || match style.get().locale {
+        Locale::Swedish => ("år efter", "år före", "Räkna från…"),
+        Locale::English => ("years after", "years before", "Anchor to…"),
+    };
+
+    view! {
+        <div class="flex gap-1 text-sm">
+            <input
+                type="number"
+                min="0"
+                class="input input-xs w-12"
+                prop:value=years
+                on:change=set_years
+            />
+            <select class="select select-xs" on:change=set_before>
+                <option value="after">{move || labels().0}</option>
+                <option value="before">{move || labels().1}</option>
+            </select>
+            <select class="select select-xs" on:change=pick>
+                <option value="" selected=move || anchored_to().is_none()>
+                    {move || labels().2}
+                </option>
+                {others}
+            </select>
+        </div>
+    }
+}
+
+/// Whether a PoI happened once or again and again, e.g. every summer
+#[component]
+pub fn RecurrenceChoice(poi: PointOfInterest) -> impl IntoView {
+    let TimelineContext { style, .. } = expect_timeline_context();
+    const CHOICES: [Recurrence; 7] = [
+        Recurrence::EverySeason(Season::Spring),
+        Recurrence::EverySeason(Season::Summer),
+        Recurrence::EverySeason(Season::Fall),
+        Recurrence::EverySeason(Season::Winter),
+        Recurrence::Every(Duration::Weeks(1)),
+        Recurrence::Every(Duration::Months(1)),
+        Recurrence::Every(Duration::Years(1)),
+    ];
+    let pick = move |ev| {
+        let picked = event_target_value(&ev).parse::<usize>().ok();
+        poi.set_recurs(picked.and_then(|i| CHOICES.get(i).copied()));
+    };
+    let once = move || match style.get().locale {
+        Locale::Swedish => "En gång",
+        Locale::English => "Once",
+    };
+    let choices = move || {
+        let style = style.get();
+        CHOICES
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                view! {
+                    <option value=i selected=move || poi.recurs() == Some(*r)>
+                        {style.recurrence(r)}
+                    </option>
+                }
+            })
+            .collect_view()
+    };
+
+    view! {
+        <select class="select select-xs" on:change=pick>
+            <option value="" selected=move || poi.recurs().is_none()>
+                {once}
+            </option>
+            {choices}
+        </select>
+    }
+}
+
+/// How faint a PoI is drawn. The less certain, the fainter, and faintest when
+/// the tag filter does not let it through. The faintest that applies wins.
+fn opacity(certainty: Option<Certainty>, tentative: bool, filtered_out: bool) -> &'static str {
+    [
+        (filtered_out, "opacity-30"),
+        (certainty == Some(Certainty::Guess), "opacity-40"),
+        (tentative, "opacity-60"),
+        (certainty == Some(Certainty::Uncertain), "opacity-70"),
+    ]
+    .into_iter()
+    .find_map(|(applies, class)| applies.then_some(class))
+    .unwrap_or_default()
+}
+
+/// How certain the date of a PoI is and how the one who told it came to know
+#[component]
+pub fn CalibrationChoice(poi: PointOfInterest) -> impl IntoView {
+    let TimelineContext { style, .. } = expect_timeline_context();
+    const CERTAINTIES: [Certainty; 4] = [
+        Certainty::Guess,
+        Certainty::Uncertain,
+        Certainty::Likely,
+        Certainty::Certain,
+    ];
+    const RELIABILITIES: [Reliability; 4] = [
+        Reliability::Hearsay,
+        Reliability::Remembered,
+        Reliability::Witnessed,
+        Reliability::Documented,
+    ];
+    // Bedöms den först nu utgår den från att innehållet är lika säkert
+    let pick_certainty = move |ev| {
+        let picked = event_target_value(&ev).parse::<usize>().ok();
+        let calibration = picked.and_then(|i| CERTAINTIES.get(i)).map(|date| {
+            let c = poi.calibration().unwrap_or(Calibration {
+                content: *date,
+                ..Default::default()
+            });
+            Calibration { date: *date, ..c }
+        });
+        poi.set_calibration(calibration);
+    };
+    let pick_reliability = move |ev| {
+        let picked = event_target_value(&ev).parse::<usize>().ok();
+        if let Some(reliability) = picked.and_then(|i| RELIABILITIES.get(i)) {
+            let c = poi.calibration().unwrap_or_default();
+            poi.set_calibration(Some(Calibration {
+                reliability: *reliability,
+                ..c
+            }));
+        }
+    };
+    let unassessed = move || match style.get().locale {
+        Locale::Swedish => "Ej bedömd",
+        Locale::English => "Not assessed",
+    };
+    let certainties = move || {
+        let style = style.get();
+        CERTAINTIES
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                let picked = move || poi.calibration().is_some_and(|p| p.date == *c);
+                view! {
+                    <option value=i selected=picked>
+                        {style.certainty(c)}
+                    </option>
+                }
+            })
+            .collect_view()
+    };
+    let reliabilities = move || {
+        let style = style.get();
+        RELIABILITIES
+            .iter()
+            .enumerate()
+            .map(|(i, r)| {
+                let picked = move || poi.calibration().is_some_and(|p| p.reliability == *r);
+                view! {
+                    <option value=i selected=picked>
+                        {style.reliability(r)}
+                    </option>
+                }
+            })
+            .collect_view()
+    };
+
+    view! {
+        <div class="flex gap-1 text-sm">
+            <select class="select select-xs" on:change=pick_certainty>
+                <option value="" selected=move || poi.calibration().is_none()>
+                    {unassessed}
+                </option>
+                {certainties}
+            </select>
+            <select
+                class="select select-xs"
+                disabled=move || poi.calibration().is_none()
+                on:change=pick_reliability
+            >
+                {reliabilities}
+            </select>
+        </div>
+    }
+}
+
+/// Who told what and when, and everything else traced back to the same source
+#[component]
+pub fn SourceDetails(id: Identifier) -> impl IntoView {
+    let TimelineContext {
+        chronology, style, ..
+    } = expect_timeline_context();
+    move || {
+        let source = chronology.source(&id)?;
+        let style = style.get();
+        let told = style.source(&source, &chronology);
+        let source_of = match style.locale {
+            Locale::Swedish => "Källa till",
+            Locale::English => "Source of",
+        };
+        let cited_by = chronology
+            .cited_by(&id)
+            .into_iter()
+            .filter_map(|p| chronology.poi(&p))
+            .map(|p| {
+                let poi = p.identity();
+                view! {
+                    <li>
+                        <button class="link" on:click=move |_| navigate_to_(&poi)>
+                            {p.name()}
+                        </button>
+                        " "
+                        <span class="italic">{style.period(&p, &chronology)}</span>
+                    </li>
+                }
+            })
+            .collect_view();
+        Some(view! {
+            <div class="text-sm border-l-2 border-blue-800 pl-2">
+                <p class="font-bold">{told}</p>
+                <p>{source_of}</p>
+                <ul>{cited_by}</ul>
+            </div>
+        })
+    }
+}
+
+/// More occurrences than this would not be told apart anyway
+const MAX_OCCURRENCES: usize = 1000;
+
 #[enum_dispatch(Choice)]
 trait PresentsChoices {
     fn proffer(&self) -> View;
@@ -252,8 +1829,6 @@ trait PresentsChoices {
 #[enum_dispatch(Choice)]
 trait Identifies {
     fn identify(&self) -> Uuid;
-    fn child_of(&self) -> Option<Uuid>;
-    fn current(&self) -> View;
 }
 
 type ChooseMood = UserMayChoose<Mood>;
@@ -264,6 +1839,7 @@ type ChooseString = UserMayChoose<String>;
 pub(crate) enum Choice {
     ChooseMood,
     ChooseString,
+    TypedDate,
 }
 
 #[derive(Clone, Debug)]
@@ -272,32 +1848,14 @@ where
     T: 'static + IntoView + Clone,
 {
     id: Uuid,
-    parent: Option<Uuid>,
-    starting: Option<T>,
     callback: Callback<T>,
 }
 
-#[derive(Clone, Debug)]
-pub(crate) struct BecomeChoice<T> {
-    id: Option<Uuid>,
-    of: PhantomData<T>,
-}
-
-impl BecomeChoice<String> {
-    fn with_starting(&self, starting: String, callme: Callback<String>) -> Choice {
-        let mut choice = UserMayChoose::<String>::new(callme);
-        choice.starting = Some(starting);
-        Choice::from(choice)
-    }
-}
-
 impl<T: IntoView + Clone> UserMayChoose<T> {
     fn new(callback: impl Into<Callback<T>>) -> Self {
         Self {
             id: Uuid::new_v4(),
-            starting: None,
             callback: callback.into(),
-            parent: None,
         }
     }
 }
@@ -306,19 +1864,30 @@ impl<T: IntoView + Clone> Identifies for UserMayChoose<T> {
     fn identify(&self) -> Uuid {
         self.id
     }
+}
 
-    fn child_of(&self) -> Option<Uuid> {
-        self.parent
+impl PresentsChoices for UserMayChoose<String> {
+    fn proffer(&self) -> View {
+        view! { <StingChoice id=self.identify() consequence=self.callback/> }.into_view()
     }
+}
 
-    fn current(&self) -> View {
-        self.starting.clone().into_view()
+/// A point in time, typed the way it is told
+#[derive(Clone, Debug)]
+pub(crate) struct TypedDate {
+    id: Uuid,
+    callback: Callback<PointInTime>,
+}
+
+impl Identifies for TypedDate {
+    fn identify(&self) -> Uuid {
+        self.id
     }
 }
 
-impl PresentsChoices for UserMayChoose<String> {
+impl PresentsChoices for TypedDate {
     fn proffer(&self) -> View {
-        view! { <StingChoice id=self.identify() consequence=self.callback/> }.into_view()
+        view! { <StingChoice id=self.id read=self.callback/> }.into_view()
     }
 }
 
@@ -406,8 +1975,15 @@ where
     }
 }
 
+/// Free text. With `read`, the text is only taken once it reads as a point in time,
+/// which is then passed on.
 #[component]
-pub fn StingChoice(id: Uuid, consequence: Callback<String>) -> impl IntoView {
+pub fn StingChoice(
+    id: Uuid,
+    #[prop(optional)] consequence: Option<Callback<String>>,
+    #[prop(optional)] read: Option<Callback<PointInTime>>,
+) -> impl IntoView {
+    let TimelineContext { chronology, .. } = expect_timeline_context();
     let (r_choice, w_choice) = create_signal(String::new());
     let HotkeysContext { active_scopes, .. } = use_hotkeys_context();
     let current_scoped = store_value(active_scopes.get_untracked());
@@ -419,9 +1995,19 @@ pub fn StingChoice(id: Uuid, consequence: Callback<String>) -> impl IntoView {
         active_scopes.set_untracked(HashSet::<String>::from(["*".to_string(), id.to_string()]));
         log!("{:?}", active_scopes.get_untracked());
     });
-    on_cleanup(move || reset_hotkey_scopes());
+    on_cleanup(reset_hotkey_scopes);
     use_hotkeys!(("ctrl+enter", id.to_string()) => move |_| {
-        consequence(r_choice());
+        let text = r_choice();
+        if let Some(read) = read {
+            // TimeReading visar varför det inte gick att läsa
+            let Ok(when) = parse(&text, &chronology.parse_context(), &chronology) else {
+                return;
+            };
+            read(when);
+        }
+        if let Some(consequence) = consequence {
+            consequence(text);
+        }
     });
     view! {
         <Dialog id=id>
@@ -430,9 +2016,41 @@ pub fn StingChoice(id: Uuid, consequence: Callback<String>) -> impl IntoView {
                 prop:value=r_choice
                 on:input=move |e| w_choice(event_target_value(&e))
             />
+            <TimeReading text=r_choice.into()/>
         </Dialog>
     }
 }
+
+/// How typed text reads as a point in time, so a date can be written the way it is told
+#[component]
+fn TimeReading(text: Signal<String>) -> impl IntoView {
+    let TimelineContext {
+        chronology, style, ..
+    } = expect_timeline_context();
+    let reading = move || {
+        let text = text();
+        if text.trim().is_empty() {
+            return None;
+        }
+        let style = style.get();
+        Some(
+            parse(&text, &chronology.parse_context(), &chronology)
+                .map_err(|e| style.parse_error(&e, &chronology))
+                .and_then(|p| {
+                    p.resolve(&chronology)
+                        .map_err(|e| style.error(&e, &chronology))
+                })
+                .map_or_else(
+                    |e| e,
+                    |i| match style.locale {
+                        Locale::Swedish => format!("Läses som {}", style.interval(&i)),
+                        Locale::English => format!("Reads as {}", style.interval(&i)),
+                    },
+                ),
+        )
+    };
+    view! { <p class="text-sm opacity-60">{reading}</p> }
+}
 // Saker som behöver kontrolleras
 // Om dialogen syns
 // Om man är klar med dialogen
@@ -442,7 +2060,7 @@ pub fn StingChoice(id: Uuid, consequence: Callback<String>) -> impl IntoView {
 // Generera kod för det på något sätt
 #[component]
 pub fn Modals() -> impl IntoView {
-    let (r_show_modal, w_show_modal) = create_signal(true);
+    let (r_show_modal, _w_show_modal) = create_signal(true);
     let queue = expect_context::<Queue>();
     view! {
         <Show when=r_show_modal fallback=|| ()>
@@ -464,3 +2082,43 @@ pub fn Dialog(id: Uuid, children: Children) -> impl IntoView {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn draws_the_less_certain_fainter() {
+        assert_eq!(opacity(None, false, false), "");
+        assert_eq!(opacity(Some(Certainty::Certain), false, false), "");
+        assert_eq!(opacity(Some(Certainty::Likely), false, false), "");
+        assert_eq!(
+            opacity(Some(Certainty::Uncertain), false, false),
+            "opacity-70"
+        );
+        assert_eq!(opacity(Some(Certainty::Guess), false, false), "opacity-40");
+        // Det blekaste som gäller vinner
+        assert_eq!(
+            opacity(Some(Certainty::Uncertain), true, false),
+            "opacity-60"
+        );
+        assert_eq!(opacity(Some(Certainty::Guess), true, false), "opacity-40");
+        assert_eq!(opacity(None, false, true), "opacity-30");
+        assert_eq!(opacity(Some(Certainty::Guess), true, true), "opacity-30");
+    }
+
+    #[test]
+    fn steps_the_certainty_filter_round() {
+        let steps = std::iter::successors(Some(stricter(None)), |l| l.map(|l| stricter(Some(l))))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            steps,
+            vec![
+                Some(Certainty::Guess),
+                Some(Certainty::Uncertain),
+                Some(Certainty::Likely),
+                Some(Certainty::Certain),
+                None,
+            ]
+        );
+    }
+}
@@ -1,2 +1,3 @@
+pub mod entity;
 pub mod home;
 pub mod not_found;
@@ -0,0 +1,609 @@
+use chrono::{Datelike, NaiveDate, Weekday};
+
+use crate::types::{
+    Duration, Identifier, Lookup, Month, PointInTime, RangeEnd, Season, Stadium, Term, TimeError,
+};
+
+/// What a typed date may need to be understood
+#[derive(Debug, Clone, Copy)]
+pub struct ParseContext {
+    /// Whose age "när han var 5" refers to
+    pub about: Identifier,
+    /// What "3 år sedan" is counted back from
+    pub today: NaiveDate,
+}
+
+/// Why a typed date could not be understood
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    Empty,
+    /// A word that is neither a month, a season, a number or anything else known
+    Unknown(String),
+    /// A month or season without a year to go with it
+    MissingYear(String),
+    /// An age or an amount of time without a number. Holds an example of what
+    /// was expected.
+    MissingNumber(&'static str),
+    /// An amount of time without a unit, e.g. "3 sedan"
+    MissingUnit,
+    /// Written like a date, but not a date in the calendar
+    InvalidDate(String),
+    /// Read, but what it refers to does not come down to any dates
+    Time(TimeError),
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "Nothing to read"),
+            ParseError::Unknown(w) => write!(f, "Did not understand \"{w}\""),
+            ParseError::MissingYear(w) => write!(f, "\"{w}\" needs a year, e.g. \"{w} 2014\""),
+            ParseError::MissingNumber(e) => write!(f, "Needs a number, e.g. \"{e}\""),
+            ParseError::MissingUnit => write!(f, "Needs a unit, e.g. years or months"),
+            ParseError::InvalidDate(d) => write!(f, "\"{d}\" is not a date"),
+            ParseError::Time(e) => write!(f, "{e}"),
+        }
+    }
+}
+
+/// Reads a date the way people tell it, in Swedish or English, keeping the
+/// precision it was given in: "våren 2014", "hösten -09", "mars 2010",
+/// "när han var 5", "3 år sedan", "summer 2013", "2008-05-12" or
+/// "vårterminen i årskurs 3". Weeks are read as "vecka 34 2015" and ranges as
+/// "2015-08-10 till 2015-08-21" or "åk 1-3". Ranges are checked against what
+/// they refer to through the lookup.
+pub fn parse(
+    input: &str,
+    ctx: &ParseContext,
+    lookup: &impl Lookup,
+) -> Result<PointInTime, ParseError> {
+    let text = input.trim().to_lowercase().replace('–', " – ");
+    let words = text
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| c == ',' || c == '.'))
+        .filter(|w| !w.is_empty() && !FILLER.contains(w))
+        .flat_map(|w| match numbers_between(w) {
+            Some((from, to)) => vec![from, "–", to],
+            None => vec![w],
+        })
+        .collect::<Vec<_>>();
+    if let Some(at) = words.iter().position(|w| RANGE.contains(w)) {
+        return range(&words[..at], &words[at + 1..], ctx, lookup);
+    }
+    read(&words, ctx)
+}
+
+fn read(words: &[&str], ctx: &ParseContext) -> Result<PointInTime, ParseError> {
+    match words {
+        [] => Err(ParseError::Empty),
+        [.., last] if AGO.contains(last) => ago(&words[..words.len() - 1], ctx),
+        [first, ..] if week_number(first).is_some() || WEEK.contains(first) => week(words, ctx),
+        _ if words.iter().any(|w| is_school(w)) => school(words, ctx),
+        _ if words.iter().any(|w| is_age(w)) => age(words, ctx),
+        [week] if week.contains("-w") => {
+            let (y, w) = week.split_once("-w").unwrap_or_default();
+            y.parse::<i32>()
+                .ok()
+                .zip(w.parse::<u32>().ok())
+                .and_then(|(y, w)| NaiveDate::from_isoywd_opt(y, w, Weekday::Mon))
+                .map(|d| PointInTime::from(d.iso_week()))
+                .ok_or_else(|| ParseError::InvalidDate(week.to_string()))
+        }
+        [date] if date.len() == 10 && date.contains('-') => {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map(PointInTime::Day)
+                .map_err(|_| ParseError::InvalidDate(date.to_string()))
+        }
+        [month] if month.len() == 7 && month.contains('-') => {
+            let (y, m) = month.split_once('-').unwrap_or_default();
+            year(y, ctx)
+                .zip(m.parse::<u8>().ok())
+                .and_then(|ym| PointInTime::try_from(ym).ok())
+                .ok_or_else(|| ParseError::InvalidDate(month.to_string()))
+        }
+        [word] => match year(word, ctx) {
+            Some(y) => Ok(PointInTime::Year(y)),
+            None if season(word).is_some() || month(word).is_some() => {
+                Err(ParseError::MissingYear(word.to_string()))
+            }
+            None => Err(ParseError::Unknown(word.to_string())),
+        },
+        [word, y] => {
+            let y = year_after_month(y, ctx).ok_or_else(|| ParseError::Unknown(y.to_string()))?;
+            if let Some(s) = season(word) {
+                Ok(PointInTime::Season(y, s))
+            } else if let Some(m) = month(word) {
+                Ok(PointInTime::Month(y, m))
+            } else {
+                Err(ParseError::Unknown(word.to_string()))
+            }
+        }
+        [first, ..] => Err(ParseError::Unknown(first.to_string())),
+    }
+}
+
+const FILLER: &[&str] = &[
+    "i", "in", "of", "the", "för", "ca", "cirka", "about", "around", "från", "from",
+];
+const RANGE: &[&str] = &["till", "to", "–", "-"];
+const WEEK: &[&str] = &["vecka", "v", "week", "wk"];
+const AGO: &[&str] = &["sedan", "sen", "ago"];
+const AGE: &[&str] = &[
+    "när", "var", "vid", "ålder", "åldern", "gammal", "when", "was", "at", "age", "aged", "old",
+];
+
+/// From the start of one point in time to the end of the other, each kept as
+/// told. A side that is only a number borrows the words of the other, so that
+/// "åk 1 – 3" reads as "åk 1 – åk 3" and "5 – 7 års ålder" as "5 års ålder –
+/// 7 års ålder".
+fn range(
+    from: &[&str],
+    to: &[&str],
+    ctx: &ParseContext,
+    lookup: &impl Lookup,
+) -> Result<PointInTime, ParseError> {
+    let side = |words: &[&str], other: &[&str]| {
+        let borrowed = match (words, other.iter().position(|w| number(w).is_some())) {
+            ([n], Some(at)) if year(n, ctx).is_none() && other.len() > 1 => {
+                let mut borrowed = other.to_vec();
+                borrowed[at] = n;
+                borrowed
+            }
+            _ => words.to_vec(),
+        };
+        RangeEnd::try_from(read(&borrowed, ctx)?).map_err(|_| ParseError::Unknown(words.join(" ")))
+    };
+    let range = PointInTime::Range(side(from, to)?, side(to, from)?);
+    range.resolve(lookup).map_err(ParseError::Time)?;
+    Ok(range)
+}
+
+/// Two short numbers written together as "5-7"
+fn numbers_between(word: &str) -> Option<(&str, &str)> {
+    let (from, to) = word.split_once('-')?;
+    let short = |n: &str| n.len() <= 2 && n.parse::<u32>().is_ok();
+    (short(from) && short(to)).then_some((from, to))
+}
+
+/// "vecka 34 2015", "v34 2015" or "week 34 of 2015"
+fn week(words: &[&str], ctx: &ParseContext) -> Result<PointInTime, ParseError> {
+    let (w, rest) = match words {
+        [w, rest @ ..] if week_number(w).is_some() => (week_number(w), rest),
+        [_, n, rest @ ..] => (n.parse::<u32>().ok(), rest),
+        _ => (None, &[][..]),
+    };
+    let w = w.ok_or(ParseError::MissingNumber("vecka 34 2015"))?;
+    let y = match rest {
+        [] => return Err(ParseError::MissingYear(words.join(" "))),
+        [y] => year(y, ctx).ok_or_else(|| ParseError::Unknown(y.to_string()))?,
+        [_, extra, ..] => return Err(ParseError::Unknown(extra.to_string())),
+    };
+    NaiveDate::from_isoywd_opt(y, w, Weekday::Mon)
+        .map(|d| PointInTime::from(d.iso_week()))
+        .ok_or_else(|| ParseError::InvalidDate(words.join(" ")))
+}
+
+/// The number of a week written together with its "v", as in "v34"
+fn week_number(word: &str) -> Option<u32> {
+    word.strip_prefix('v')?.parse().ok()
+}
+
+/// "3 år", "tre månader" and so on, counted back from today
+fn ago(words: &[&str], ctx: &ParseContext) -> Result<PointInTime, ParseError> {
+    let n = words
+        .iter()
+        .find_map(|w| number(w))
+        .ok_or(ParseError::MissingNumber("3 år sedan"))?;
+    let unit = words
+        .iter()
+        .filter(|w| number(w).is_none())
+        .map(|w| (w, unit(w, n)))
+        .next();
+    match unit {
+        Some((_, Some(time))) => Ok(PointInTime::ago(time, ctx.today)),
+        Some((w, None)) => Err(ParseError::Unknown(w.to_string())),
+        None => Err(ParseError::MissingUnit),
+    }
+}
+
+/// Whether the word says that a number is an age, as "var" or "5-årsåldern" do
+fn is_age(word: &str) -> bool {
+    AGE.contains(&word) || AGE_SUFFIXES.iter().any(|s| word.ends_with(s))
+}
+
+const AGE_SUFFIXES: &[&str] = &["-årsåldern", "-åring"];
+
+/// "när han var 5", "vid 5 års ålder", "i 5-årsåldern", "at 5" and so on
+fn age(words: &[&str], ctx: &ParseContext) -> Result<PointInTime, ParseError> {
+    let unknown = words.iter().find(|w| {
+        !AGE.contains(w) && !PRONOUNS.contains(w) && number(w).is_none() && unit(w, 1).is_none()
+    });
+    if let Some(w) = unknown {
+        return Err(ParseError::Unknown(w.to_string()));
+    }
+    words
+        .iter()
+        .find_map(|w| number(w))
+        .and_then(|n| i8::try_from(n).ok())
+        .map(|n| PointInTime::ChronologicalAge(n, ctx.about))
+        .ok_or(ParseError::MissingNumber("när han var 5"))
+}
+
+const PRONOUNS: &[&str] = &["han", "hon", "hen", "den", "he", "she", "they", "it"];
+
+const GRADE: &[&str] = &["årskurs", "åk", "klass", "grade"];
+const TERM: &[&str] = &["terminen", "term"];
+
+fn is_school(word: &str) -> bool {
+    stadium(word).is_some()
+        || term(word).is_some()
+        || nickname(word).is_some()
+        || GRADE.contains(&word)
+        || TERM.contains(&word)
+}
+
+/// "vårterminen i årskurs 3", "mellanstadiet", "ht i trean" and so on
+fn school(words: &[&str], ctx: &ParseContext) -> Result<PointInTime, ParseError> {
+    // "spring term" is a term, where "spring" alone would be a season
+    let term_at = words
+        .iter()
+        .position(|w| term(w).is_some() || TERM.contains(w));
+    let term = term_at.and_then(|i| {
+        term(words[i]).or_else(|| {
+            let season = i.checked_sub(1).and_then(|i| season(words[i]))?;
+            match season {
+                Season::Spring => Some(Term::Spring),
+                Season::Fall => Some(Term::Fall),
+                Season::Summer | Season::Winter => None,
+            }
+        })
+    });
+    let rest = words
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| term.is_none() || term_at.is_none_or(|t| *i > t))
+        .map(|(_, w)| *w)
+        .collect::<Vec<_>>();
+    let grade = match rest.as_slice() {
+        [word] if GRADE.contains(word) => return Err(ParseError::MissingNumber("årskurs 3")),
+        [word] => match (stadium(word), nickname(word)) {
+            (Some(Stadium::PreschoolClass), _) => 0,
+            (Some(s), _) if term.is_none() => return Ok(PointInTime::Stadium(s, ctx.about)),
+            (_, Some(grade)) => grade,
+            _ => return Err(ParseError::Unknown(word.to_string())),
+        },
+        [word, n] if GRADE.contains(word) => number(n)
+            .and_then(|n| u8::try_from(n).ok())
+            .filter(|n| *n <= 12)
+            .ok_or_else(|| ParseError::Unknown(n.to_string()))?,
+        [word, ..] if !GRADE.contains(word) => return Err(ParseError::Unknown(word.to_string())),
+        _ => return Err(ParseError::MissingNumber("årskurs 3")),
+    };
+    Ok(match term {
+        Some(term) => PointInTime::TermOf(term, grade, ctx.about),
+        None => PointInTime::Grade(grade, ctx.about),
+    })
+}
+
+fn term(word: &str) -> Option<Term> {
+    match word {
+        "vårterminen" | "vt" => Some(Term::Spring),
+        "höstterminen" | "ht" => Some(Term::Fall),
+        _ => None,
+    }
+}
+
+fn stadium(word: &str) -> Option<Stadium> {
+    match word {
+        "förskola" | "förskolan" | "dagis" | "preschool" => Some(Stadium::Preschool),
+        "förskoleklass" | "förskoleklassen" | "sexårs" => Some(Stadium::PreschoolClass),
+        "lågstadiet" | "lågstadium" => Some(Stadium::Lower),
+        "mellanstadiet" | "mellanstadium" => Some(Stadium::Middle),
+        "högstadiet" | "högstadium" => Some(Stadium::Upper),
+        "gymnasiet" | "gymnasium" | "gymnasie" => Some(Stadium::UpperSecondary),
+        _ => None,
+    }
+}
+
+/// What the grades are called among children, "ettan" for the first
+fn nickname(word: &str) -> Option<u8> {
+    const NICKNAMES: &[&str] = &[
+        "ettan", "tvåan", "trean", "fyran", "femman", "sexan", "sjuan", "åttan", "nian",
+    ];
+    NICKNAMES
+        .iter()
+        .position(|n| *n == word)
+        .map(|i| i as u8 + 1)
+}
+
+/// Digits or a number word, up to twelve. "5-årsåldern" and "5 års" count as 5.
+fn number(word: &str) -> Option<u32> {
+    let word = AGE_SUFFIXES
+        .iter()
+        .fold(word, |w, suffix| w.trim_end_matches(suffix));
+    if let Ok(n) = word.parse::<u32>() {
+        return Some(n);
+    }
+    const WORDS: &[(&[&str], u32)] = &[
+        (&["en", "ett", "one", "a", "an"], 1),
+        (&["två", "two"], 2),
+        (&["tre", "three"], 3),
+        (&["fyra", "four"], 4),
+        (&["fem", "five"], 5),
+        (&["sex", "six"], 6),
+        (&["sju", "seven"], 7),
+        (&["åtta", "eight"], 8),
+        (&["nio", "nine"], 9),
+        (&["tio", "ten"], 10),
+        (&["elva", "eleven"], 11),
+        (&["tolv", "twelve"], 12),
+    ];
+    WORDS
+        .iter()
+        .find(|(words, _)| words.contains(&word))
+        .map(|(_, n)| *n)
+}
+
+fn unit(word: &str, n: u32) -> Option<Duration> {
+    match word {
+        "dag" | "dagar" | "day" | "days" => Some(Duration::Days(n)),
+        "vecka" | "veckor" | "week" | "weeks" => Some(Duration::Weeks(n)),
+        "månad" | "månader" | "month" | "months" => Some(Duration::Months(n)),
+        "år" | "års" | "year" | "years" => Some(Duration::Years(n)),
+        _ => None,
+    }
+}
+
+/// "2014", or a short year marked as one, "-09" or "'09", taken to be the
+/// latest such year that is not in the future. A bare "09" may as well be a
+/// day, a grade or an age, so it is only a year after a month or season.
+fn year(word: &str, ctx: &ParseContext) -> Option<i32> {
+    match word.strip_prefix(['-', '\'', '’']) {
+        Some(short) => short_year(short, ctx),
+        None if word.len() == 4 => word.parse().ok(),
+        None => None,
+    }
+}
+
+/// The year of "hösten 09" or "mars 10", where it cannot be anything else
+fn year_after_month(word: &str, ctx: &ParseContext) -> Option<i32> {
+    year(word, ctx).or_else(|| short_year(word, ctx))
+}
+
+fn short_year(short: &str, ctx: &ParseContext) -> Option<i32> {
+    if short.len() != 2 {
+        return None;
+    }
+    let yy = short.parse::<i32>().ok()?;
+    let century = ctx.today.year() - ctx.today.year().rem_euclid(100);
+    let y = century + yy;
+    Some(if y > ctx.today.year() { y - 100 } else { y })
+}
+
+fn season(word: &str) -> Option<Season> {
+    match word {
+        "våren" | "vår" | "våras" | "spring" => Some(Season::Spring),
+        "sommaren" | "sommar" | "somras" | "summer" => Some(Season::Summer),
+        "hösten" | "höst" | "höstas" | "fall" | "autumn" => Some(Season::Fall),
+        "vintern" | "vinter" | "vintras" | "winter" => Some(Season::Winter),
+        _ => None,
+    }
+}
+
+fn month(word: &str) -> Option<Month> {
+    let n = match word {
+        "januari" | "january" | "jan" => 1,
+        "februari" | "february" | "feb" => 2,
+        "mars" | "march" | "mar" => 3,
+        "april" | "apr" => 4,
+        "maj" | "may" => 5,
+        "juni" | "june" | "jun" => 6,
+        "juli" | "july" | "jul" => 7,
+        "augusti" | "august" | "aug" => 8,
+        "september" | "sep" | "sept" => 9,
+        "oktober" | "october" | "okt" | "oct" => 10,
+        "november" | "nov" => 11,
+        "december" | "dec" => 12,
+        _ => return None,
+    };
+    Month::try_from(n).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Interval, Person, PointOfInterest};
+
+    fn ctx() -> ParseContext {
+        ParseContext {
+            about: Identifier::default(),
+            today: NaiveDate::from_ymd_opt(2024, 5, 1).unwrap(),
+        }
+    }
+
+    #[test]
+    fn reads_seasons_months_and_years() {
+        let ctx = ctx();
+        assert_eq!(
+            parse("våren 2014", &ctx, &()),
+            Ok(PointInTime::Season(2014, Season::Spring))
+        );
+        assert_eq!(
+            parse("hösten -09", &ctx, &()),
+            Ok(PointInTime::Season(2009, Season::Fall))
+        );
+        assert_eq!(
+            parse("Summer 2013", &ctx, &()),
+            Ok(PointInTime::Season(2013, Season::Summer))
+        );
+        assert_eq!(
+            parse("mars 2010", &ctx, &()),
+            Ok(PointInTime::Month(2010, Month::March))
+        );
+        assert_eq!(parse("2008", &ctx, &()), Ok(PointInTime::Year(2008)));
+        // Korta årtal är de senaste som inte ligger i framtiden
+        assert_eq!(parse("'95", &ctx, &()), Ok(PointInTime::Year(1995)));
+    }
+
+    #[test]
+    fn reads_a_bare_short_number_as_a_year_only_after_a_month_or_season() {
+        let ctx = ctx();
+        assert_eq!(
+            parse("12", &ctx, &()),
+            Err(ParseError::Unknown("12".to_string()))
+        );
+        assert_eq!(parse("'12", &ctx, &()), Ok(PointInTime::Year(2012)));
+        assert_eq!(
+            parse("hösten 09", &ctx, &()),
+            Ok(PointInTime::Season(2009, Season::Fall))
+        );
+        assert_eq!(
+            parse("mars 12", &ctx, &()),
+            Ok(PointInTime::Month(2012, Month::March))
+        );
+        // Så ett ensamt "12" lånar orden från andra sidan
+        assert_eq!(
+            parse("hösten 09 – 12", &ctx, &()),
+            Ok(PointInTime::Range(
+                RangeEnd::Season(2009, Season::Fall),
+                RangeEnd::Season(2012, Season::Fall),
+            ))
+        );
+    }
+
+    #[test]
+    fn reads_dates() {
+        assert_eq!(
+            parse("2008-05-12", &ctx(), &()),
+            Ok(PointInTime::Day(
+                NaiveDate::from_ymd_opt(2008, 5, 12).unwrap()
+            ))
+        );
+        assert_eq!(
+            parse("2008-02-30", &ctx(), &()),
+            Err(ParseError::InvalidDate("2008-02-30".to_string()))
+        );
+    }
+
+    #[test]
+    fn reads_ages_of_whom_the_timeline_is_about() {
+        let ctx = ctx();
+        let five = Ok(PointInTime::ChronologicalAge(5, ctx.about));
+        assert_eq!(parse("när han var 5", &ctx, &()), five);
+        assert_eq!(parse("vid fem års ålder", &ctx, &()), five);
+        assert_eq!(parse("i 5-årsåldern", &ctx, &()), five);
+        assert_eq!(parse("at age 5", &ctx, &()), five);
+        assert_eq!(
+            parse("när hon var", &ctx, &()),
+            Err(ParseError::MissingNumber("när han var 5"))
+        );
+    }
+
+    #[test]
+    fn reads_time_ago_from_today() {
+        let ctx = ctx();
+        assert_eq!(
+            parse("3 år sedan", &ctx, &()),
+            Ok(PointInTime::ago(Duration::Years(3), ctx.today))
+        );
+        assert_eq!(
+            parse("two months ago", &ctx, &()),
+            Ok(PointInTime::ago(Duration::Months(2), ctx.today))
+        );
+        assert_eq!(parse("3 sedan", &ctx, &()), Err(ParseError::MissingUnit));
+        assert_eq!(
+            parse("år sedan", &ctx, &()),
+            Err(ParseError::MissingNumber("3 år sedan"))
+        );
+    }
+
+    #[test]
+    fn says_what_it_did_not_understand() {
+        let ctx = ctx();
+        assert_eq!(parse("  ", &ctx, &()), Err(ParseError::Empty));
+        assert_eq!(
+            parse("våren", &ctx, &()),
+            Err(ParseError::MissingYear("våren".to_string()))
+        );
+        assert_eq!(
+            parse("blåren 2014", &ctx, &()),
+            Err(ParseError::Unknown("blåren".to_string()))
+        );
+    }
+
+    #[test]
+    fn reads_weeks() {
+        let ctx = ctx();
+        let week = Ok(PointInTime::Week(2015, 34));
+        assert_eq!(parse("vecka 34 2015", &ctx, &()), week);
+        assert_eq!(parse("v34 2015", &ctx, &()), week);
+        assert_eq!(parse("week 34 of 2015", &ctx, &()), week);
+        assert_eq!(parse("2015-W34", &ctx, &()), week);
+        assert_eq!(
+            parse("vecka 2015", &ctx, &()),
+            Err(ParseError::MissingYear("vecka 2015".to_string()))
+        );
+    }
+
+    /// Only the person the timeline is about
+    struct About(Person);
+
+    impl Lookup for About {
+        fn person(&self, _id: &Identifier) -> Option<Person> {
+            Some(self.0.clone())
+        }
+
+        fn poi(&self, _id: &Identifier) -> Option<PointOfInterest> {
+            None
+        }
+
+        fn origo(&self) -> Option<Interval> {
+            self.0.born().ok()
+        }
+    }
+
+    #[test]
+    fn reads_ranges_as_told() {
+        let ctx = ctx();
+        let about = About(Person::new("Långben".to_string(), PointInTime::Year(2007)));
+        let day = |m, d| RangeEnd::Day(NaiveDate::from_ymd_opt(2015, m, d).unwrap());
+        assert_eq!(
+            parse("2015-08-10 till 2015-08-21", &ctx, &()),
+            Ok(PointInTime::Range(day(8, 10), day(8, 21)))
+        );
+        assert_eq!(
+            parse("2008 – 2010", &ctx, &()),
+            Ok(PointInTime::Range(
+                RangeEnd::Year(2008),
+                RangeEnd::Year(2010)
+            ))
+        );
+        // Ett ensamt tal lånar orden från andra sidan
+        let grades = Ok(PointInTime::Range(
+            RangeEnd::Grade(1, ctx.about),
+            RangeEnd::Grade(3, ctx.about),
+        ));
+        assert_eq!(parse("åk 1 – åk 3", &ctx, &about), grades);
+        assert_eq!(parse("åk 1-3", &ctx, &about), grades);
+        assert_eq!(
+            parse("5-7 års ålder", &ctx, &about),
+            Ok(PointInTime::Range(
+                RangeEnd::ChronologicalAge(5, ctx.about),
+                RangeEnd::ChronologicalAge(7, ctx.about),
+            ))
+        );
+        // Utan någon att räkna åldern från går det inte
+        assert_eq!(
+            parse("5-7 års ålder", &ctx, &()),
+            Err(ParseError::Time(TimeError::UnknownPerson(ctx.about)))
+        );
+        assert_eq!(
+            parse("2015-08-21 till 2015-08-10", &ctx, &()),
+            Err(ParseError::Time(TimeError::ReversedRange))
+        );
+        assert_eq!(
+            parse("2008 – blåren", &ctx, &()),
+            Err(ParseError::Unknown("blåren".to_string()))
+        );
+    }
+}
@@ -0,0 +1,501 @@
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+
+use crate::{
+    format::Style,
+    types::{
+        Bound, Constraint, Identifier, Identify, Interval, Lookup, Offset, PointInTime,
+        PointOfInterest, SeasonModel,
+    },
+};
+
+/// A single thing known about when a PoI started, either on its own or in
+/// relation to another PoI.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Relation {
+    /// Its start lies within these dates, either end may be open
+    Bounded {
+        poi: Identifier,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    },
+    /// Its start is offset from the start of the anchor
+    Offset {
+        poi: Identifier,
+        anchor: Identifier,
+        offset: Offset,
+    },
+    /// Its start is after, before or during the other. Only the start of the
+    /// other takes part, the end of what it is during is given as a bound.
+    Ordered {
+        poi: Identifier,
+        constraint: Constraint,
+        other: Identifier,
+    },
+}
+
+impl Relation {
+    /// The PoIs the relation says something about
+    pub fn pois(&self) -> Vec<Identifier> {
+        match self {
+            Relation::Bounded { poi, .. } => vec![*poi],
+            Relation::Offset { poi, anchor, .. } => vec![*poi, *anchor],
+            Relation::Ordered { poi, other, .. } => vec![*poi, *other],
+        }
+    }
+
+    pub fn involves(&self, id: &Identifier) -> bool {
+        self.pois().contains(id)
+    }
+
+    pub fn describe(&self, style: &Style, lookup: &impl Lookup) -> String {
+        let name = |id: &Identifier| style.name(id, lookup);
+        match self {
+            Relation::Bounded { poi, from, to } => match (from, to) {
+                (Some(f), Some(t)) => format!(
+                    "{} {} {}",
+                    name(poi),
+                    style.sv_en("inom", "within"),
+                    style.interval(&Interval::new(*f, *t))
+                ),
+                (Some(f), None) => format!(
+                    "{} {} {}",
+                    name(poi),
+                    style.sv_en("inte före", "not before"),
+                    style.date(f)
+                ),
+                (None, Some(t)) => format!(
+                    "{} {} {}",
+                    name(poi),
+                    style.sv_en("inte efter", "not after"),
+                    style.date(t)
+                ),
+                (None, None) => name(poi),
+            },
+            Relation::Offset {
+                poi,
+                anchor,
+                offset,
+            } => {
+                format!("{} {} {}", name(poi), style.offset(offset), name(anchor))
+            }
+            Relation::Ordered {
+                poi,
+                constraint,
+                other,
+            } => format!(
+                "{} {} {}",
+                name(poi),
+                style.constraint(constraint),
+                name(other)
+            ),
+        }
+    }
+}
+
+/// What could be worked out about the whole timeline at once
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Solution {
+    /// Every PoI whose start could be pinned down on both ends
+    pub narrowed: HashMap<Identifier, Interval>,
+    /// Sets of relations that cannot all hold. Leaving out any one relation of
+    /// a set makes the rest of it possible.
+    pub conflicts: Vec<Vec<Relation>>,
+}
+
+impl Solution {
+    pub fn conflicts_for(&self, id: &Identifier) -> Vec<&Vec<Relation>> {
+        self.conflicts
+            .iter()
+            .filter(|c| c.iter().any(|r| r.involves(id)))
+            .collect()
+    }
+}
+
+/// Collects what each PoI says about its own start
+pub fn relations(pois: &[PointOfInterest], lookup: &impl Lookup) -> Vec<Relation> {
+    let mut relations = Vec::new();
+    for p in pois {
+        let poi = p.identity();
+        match p.starts_as_of(lookup.as_of()) {
+            PointInTime::RelativeTo(anchor, offset) => relations.push(Relation::Offset {
+                poi,
+                anchor,
+                offset,
+            }),
+            PointInTime::Approximated(a) => {
+                for (constraint, bound) in a.constraints() {
+                    match bound {
+                        Bound::PoI(other) => {
+                            relations.push(Relation::Ordered {
+                                poi,
+                                constraint,
+                                other,
+                            });
+                            // Det som sker under något sker senast när det slutar
+                            if constraint == Constraint::During {
+                                let until = lookup.poi(&other).and_then(|o| {
+                                    o.ends_within(lookup)
+                                        .or_else(|_| o.starts_within(lookup))
+                                        .ok()
+                                });
+                                relations.extend(until.map(|u| Relation::Bounded {
+                                    poi,
+                                    from: None,
+                                    to: Some(u.latest()),
+                                }));
+                            }
+                        }
+                        Bound::Dates(i) => {
+                            let (from, to) = match constraint {
+                                Constraint::After => (i.earliest().succ_opt(), None),
+                                Constraint::Before => (None, i.latest().pred_opt()),
+                                Constraint::During => (Some(i.earliest()), Some(i.latest())),
+                            };
+                            relations.push(Relation::Bounded { poi, from, to });
+                        }
+                    }
+                }
+            }
+            // Med den felmarginal PoI:n har
+            _ => {
+                if let Ok(i) = p.starts_within(lookup) {
+                    relations.push(Relation::Bounded {
+                        poi,
+                        from: Some(i.earliest()),
+                        to: Some(i.latest()),
+                    })
+                }
+            }
+        }
+    }
+    relations
+}
+
+/// Narrows the start of every PoI as far as the relations allow, and picks out
+/// the relations that contradict each other.
+pub fn solve(relations: &[Relation], seasons: &SeasonModel) -> Solution {
+    let mut kept = relations.to_vec();
+    let mut conflicts = Vec::new();
+    let mut propagated = propagate(&kept, seasons);
+    while let Propagation::Contradiction = propagated {
+        let conflict = minimal_conflict(&kept, seasons);
+        if conflict.is_empty() {
+            break;
+        }
+        kept.retain(|r| !conflict.contains(r));
+        conflicts.push(conflict);
+        propagated = propagate(&kept, seasons);
+    }
+    let domains = match propagated {
+        Propagation::Settled(domains) | Propagation::Undecided(domains) => domains,
+        Propagation::Contradiction => HashMap::new(),
+    };
+    let narrowed = domains
+        .into_iter()
+        .filter_map(|(id, d)| d.bounded().map(|i| (id, i)))
+        .collect();
+    Solution {
+        narrowed,
+        conflicts,
+    }
+}
+
+/// Drops relations one at a time for as long as the rest still contradict each other
+fn minimal_conflict(relations: &[Relation], seasons: &SeasonModel) -> Vec<Relation> {
+    let mut conflict = relations.to_vec();
+    let mut i = 0;
+    while i < conflict.len() {
+        let mut without = conflict.clone();
+        without.remove(i);
+        if let Propagation::Contradiction = propagate(&without, seasons) {
+            conflict = without;
+        } else {
+            i += 1;
+        }
+    }
+    conflict
+}
+
+/// One end of a domain. An end that is not `known` comes from nothing but the
+/// limits of the calendar, and only serves to catch circular orderings.
+#[derive(Debug, Clone, Copy)]
+struct End {
+    date: NaiveDate,
+    known: bool,
+}
+
+impl End {
+    fn known(date: NaiveDate) -> Self {
+        Self { date, known: true }
+    }
+
+    fn succ(&self) -> Option<End> {
+        self.date.succ_opt().map(|date| End { date, ..*self })
+    }
+
+    fn pred(&self) -> Option<End> {
+        self.date.pred_opt().map(|date| End { date, ..*self })
+    }
+}
+
+/// The dates a start may still fall on
+#[derive(Debug, Clone, Copy)]
+struct Domain {
+    lo: End,
+    hi: End,
+}
+
+impl Default for Domain {
+    fn default() -> Self {
+        Self {
+            lo: End {
+                date: NaiveDate::MIN,
+                known: false,
+            },
+            hi: End {
+                date: NaiveDate::MAX,
+                known: false,
+            },
+        }
+    }
+}
+
+impl Domain {
+    fn bounded(&self) -> Option<Interval> {
+        (self.lo.known && self.hi.known).then(|| Interval::new(self.lo.date, self.hi.date))
+    }
+}
+
+/// How far tightening the domains got
+#[derive(Debug)]
+enum Propagation {
+    /// Nothing more can be narrowed
+    Settled(HashMap<Identifier, Domain>),
+    /// The relations cannot all hold
+    Contradiction,
+    /// Still narrowing when the passes ran out. Every date left out is ruled out,
+    /// but the domains may be wider than the relations allow.
+    Undecided(HashMap<Identifier, Domain>),
+}
+
+/// Tightens every domain until nothing changes, or until a domain becomes empty.
+///
+/// Without offsets the relations bound each end of a start by the same end of
+/// another, plus at most a day, the way the edges of a shortest path problem do.
+/// Such bounds settle within one pass per end, unless they go round a circle that
+/// gains a day each time, i.e. a circle of orderings where one is strict. Those
+/// circles are ruled out first. Offsets can shift dates by other amounts, so
+/// relations with offsets that have not settled by then are left undecided.
+fn propagate(relations: &[Relation], seasons: &SeasonModel) -> Propagation {
+    if orders_strictly_after_itself(relations) {
+        return Propagation::Contradiction;
+    }
+    let mut domains: HashMap<Identifier, Domain> = relations
+        .iter()
+        .flat_map(|r| r.pois())
+        .map(|id| (id, Domain::default()))
+        .collect();
+    for _ in 0..=2 * domains.len() {
+        let mut changed = false;
+        for r in relations {
+            for (id, lo, hi) in narrowing(&domains, r, seasons) {
+                let d = domains.entry(id).or_default();
+                if let Some(lo) = lo.filter(|lo| tighter(lo, &d.lo, |a, b| a > b)) {
+                    d.lo = lo;
+                    changed = true;
+                }
+                if let Some(hi) = hi.filter(|hi| tighter(hi, &d.hi, |a, b| a < b)) {
+                    d.hi = hi;
+                    changed = true;
+                }
+                if d.lo.date > d.hi.date {
+                    return Propagation::Contradiction;
+                }
+            }
+        }
+        if !changed {
+            return Propagation::Settled(domains);
+        }
+    }
+    Propagation::Undecided(domains)
+}
+
+/// Whether some PoI is ordered after itself, through orderings of which at least
+/// one is strict. Whatever their dates, they cannot all hold.
+fn orders_strictly_after_itself(relations: &[Relation]) -> bool {
+    // Från det som börjar först till det som börjar sist
+    let edges = relations
+        .iter()
+        .filter_map(|r| match r {
+            Relation::Ordered {
+                poi,
+                constraint,
+                other,
+            } => Some(match constraint {
+                Constraint::After => (*other, *poi, true),
+                Constraint::Before => (*poi, *other, true),
+                Constraint::During => (*other, *poi, false),
+            }),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let reaches = |from: Identifier, to: Identifier| {
+        let mut seen = vec![from];
+        let mut next = vec![from];
+        while let Some(at) = next.pop() {
+            for (_, later, _) in edges.iter().filter(|(e, _, _)| *e == at) {
+                if *later == to {
+                    return true;
+                }
+                if !seen.contains(later) {
+                    seen.push(*later);
+                    next.push(*later);
+                }
+            }
+        }
+        false
+    };
+    edges
+        .iter()
+        .any(|(earlier, later, strict)| *strict && (earlier == later || reaches(*later, *earlier)))
+}
+
+/// Whether the new end narrows the domain, or gives a known date for the same end
+fn tighter(new: &End, old: &End, beyond: fn(NaiveDate, NaiveDate) -> bool) -> bool {
+    beyond(new.date, old.date) || (new.date == old.date && new.known && !old.known)
+}
+
+/// The bounds one relation puts on the PoIs it involves, given what is known so far
+fn narrowing(
+    domains: &HashMap<Identifier, Domain>,
+    relation: &Relation,
+    seasons: &SeasonModel,
+) -> Vec<(Identifier, Option<End>, Option<End>)> {
+    let at = |id: &Identifier| domains.get(id).copied().unwrap_or_default();
+    match relation {
+        Relation::Bounded { poi, from, to } => {
+            vec![(*poi, from.map(End::known), to.map(End::known))]
+        }
+        Relation::Offset {
+            poi,
+            anchor,
+            offset,
+        } => {
+            let forward = at(anchor).bounded().and_then(|a| offset.apply(a, seasons));
+            let back = offset.inverse().zip(at(poi).bounded());
+            let back = back.and_then(|(inverse, p)| inverse.apply(p, seasons));
+            [(poi, forward), (anchor, back)]
+                .into_iter()
+                .filter_map(|(id, i)| i.map(|i| (*id, i)))
+                .map(|(id, i)| {
+                    (
+                        id,
+                        Some(End::known(i.earliest())),
+                        Some(End::known(i.latest())),
+                    )
+                })
+                .collect()
+        }
+        Relation::Ordered {
+            poi,
+            constraint,
+            other,
+        } => {
+            let (at_poi, at_other) = (at(poi), at(other));
+            match constraint {
+                Constraint::After => vec![
+                    (*poi, at_other.lo.succ(), None),
+                    (*other, None, at_poi.hi.pred()),
+                ],
+                Constraint::Before => vec![
+                    (*poi, None, at_other.hi.pred()),
+                    (*other, at_poi.lo.succ(), None),
+                ],
+                // Inom det andra, som alltså måste ha börjat först
+                Constraint::During => vec![
+                    (*poi, Some(at_other.lo), None),
+                    (*other, None, Some(at_poi.hi)),
+                ],
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn year(y: i32) -> (Option<NaiveDate>, Option<NaiveDate>) {
+        (
+            NaiveDate::from_ymd_opt(y, 1, 1),
+            NaiveDate::from_ymd_opt(y, 12, 31),
+        )
+    }
+
+    fn during(poi: Identifier, y: i32) -> Relation {
+        let (from, to) = year(y);
+        Relation::Bounded { poi, from, to }
+    }
+
+    fn ordered(poi: Identifier, constraint: Constraint, other: Identifier) -> Relation {
+        Relation::Ordered {
+            poi,
+            constraint,
+            other,
+        }
+    }
+
+    #[test]
+    fn narrows_along_a_chain_of_orderings() {
+        let ids = (0..8).map(|_| Identifier::default()).collect::<Vec<_>>();
+        let mut relations = vec![during(ids[0], 2008), during(ids[7], 2008)];
+        relations.extend(
+            ids.windows(2)
+                .map(|w| ordered(w[1], Constraint::After, w[0])),
+        );
+        let solution = solve(&relations, &SeasonModel::default());
+        assert!(solution.conflicts.is_empty());
+        let (first, last) = year(2008);
+        let fourth = solution.narrowed[&ids[3]];
+        assert_eq!(fourth.earliest(), first.unwrap() + chrono::Days::new(3));
+        assert_eq!(fourth.latest(), last.unwrap() - chrono::Days::new(4));
+    }
+
+    #[test]
+    fn finds_what_contradicts_the_dates_given() {
+        let (a, b) = (Identifier::default(), Identifier::default());
+        let relations = vec![
+            during(a, 2010),
+            during(b, 2008),
+            ordered(b, Constraint::After, a),
+        ];
+        let solution = solve(&relations, &SeasonModel::default());
+        assert_eq!(solution.conflicts, vec![relations.clone()]);
+        assert!(solution.narrowed.is_empty());
+    }
+
+    #[test]
+    fn finds_circles_of_strict_orderings_without_dates() {
+        let (a, b, c) = (
+            Identifier::default(),
+            Identifier::default(),
+            Identifier::default(),
+        );
+        let circle = vec![
+            ordered(b, Constraint::After, a),
+            ordered(c, Constraint::During, b),
+            ordered(a, Constraint::During, c),
+        ];
+        let solution = solve(&circle, &SeasonModel::default());
+        assert_eq!(solution.conflicts, vec![circle]);
+
+        // Att vara under varandra går däremot
+        let mutual = vec![
+            ordered(a, Constraint::During, b),
+            ordered(b, Constraint::During, a),
+        ];
+        assert!(solve(&mutual, &SeasonModel::default()).conflicts.is_empty());
+    }
+}
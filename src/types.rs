@@ -1,8 +1,12 @@
 #![allow(dead_code)]
 
-use chrono::{NaiveDate, NaiveDateTime, TimeDelta};
+use std::{ops::RangeInclusive, str::FromStr};
+
+use chrono::{Datelike, Days, IsoWeek, Local, Months, NaiveDate, NaiveDateTime, Weekday};
 use indicium::simple::Indexable;
-use leptos::{MaybeSignal, RwSignal, SignalGet};
+use leptos::{
+    MaybeSignal, RwSignal, SignalGet, SignalGetUntracked, SignalSet, SignalUpdate, SignalWith,
+};
 use uuid::Uuid;
 
 // Sketches
@@ -15,10 +19,226 @@ pub struct PeriodOfTime<T> {
 pub enum CaptureMode {}
 
 pub struct Impact;
-pub struct Calibration;
-pub struct NamedEntity;
-// struct NamedEntity<T>;
-pub struct Tag;
+/// How far a PoI can be trusted, e.g. "remembered by mother, uncertain ±1 year"
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Calibration {
+    /// How sure the date is
+    pub date: Certainty,
+    /// How sure it is that it happened as told
+    pub content: Certainty,
+    /// How far off the date may be, either way
+    pub margin: Option<Duration>,
+    pub reliability: Reliability,
+}
+
+impl Calibration {
+    /// The less certain of its date and its content
+    pub fn weakest(&self) -> Certainty {
+        self.date.min(self.content)
+    }
+
+    /// Whether the date is only roughly known, as uncertain or with a margin
+    pub fn is_rough(&self) -> bool {
+        self.date <= Certainty::Uncertain || self.margin.is_some_and(|m| !m.is_zero())
+    }
+}
+
+/// From least to most certain
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Certainty {
+    Guess,
+    Uncertain,
+    #[default]
+    Likely,
+    Certain,
+}
+
+/// How the one who told it came to know, from least to most reliable
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Reliability {
+    Hearsay,
+    #[default]
+    Remembered,
+    Witnessed,
+    Documented,
+}
+
+/// Where what is told about PoIs comes from, so that it can be traced back
+#[derive(Default, Debug, Clone)]
+pub struct Source {
+    id: Identifier,
+    kind: SourceKind,
+    /// When it was told or written down
+    date: PointInTime,
+    /// The person who told it, if anyone did
+    informant: Option<Identifier>,
+}
+
+impl Source {
+    pub fn new(kind: SourceKind, date: PointInTime) -> Self {
+        Self {
+            kind,
+            date,
+            ..Default::default()
+        }
+    }
+
+    pub fn told_by(mut self, informant: Identifier) -> Self {
+        self.informant = Some(informant);
+        self
+    }
+
+    pub fn kind(&self) -> SourceKind {
+        self.kind
+    }
+
+    pub fn date(&self) -> &PointInTime {
+        &self.date
+    }
+
+    pub fn informant(&self) -> Option<Identifier> {
+        self.informant
+    }
+}
+
+impl Identify for Source {
+    fn identity(&self) -> Identifier {
+        self.id
+    }
+}
+
+/// One source's version of when a PoI started
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Claim {
+    pub source: Identifier,
+    pub starts: PointInTime,
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum SourceKind {
+    #[default]
+    Interview,
+    SchoolRecords,
+    Journal,
+    Other,
+}
+
+impl SourceKind {
+    pub const ALL: [SourceKind; 4] = [
+        SourceKind::Interview,
+        SourceKind::SchoolRecords,
+        SourceKind::Journal,
+        SourceKind::Other,
+    ];
+}
+
+/// Someone or something PoIs mention, such as a school or a clinic
+#[derive(Default, Debug, Clone)]
+pub struct NamedEntity {
+    id: Identifier,
+    name: String,
+    kind: EntityKind,
+}
+
+impl NamedEntity {
+    pub fn new(name: String, kind: EntityKind) -> Self {
+        Self {
+            name,
+            kind,
+            ..Default::default()
+        }
+    }
+
+    pub fn kind(&self) -> EntityKind {
+        self.kind
+    }
+
+    pub fn set_kind(&mut self, kind: EntityKind) {
+        self.kind = kind;
+    }
+}
+
+impl Identify for NamedEntity {
+    fn identity(&self) -> Identifier {
+        self.id
+    }
+}
+
+impl MyName for NamedEntity {
+    fn name(&self) -> MaybeSignal<String> {
+        self.name.clone().into()
+    }
+}
+
+impl Indexable for NamedEntity {
+    fn strings(&self) -> Vec<String> {
+        vec![self.name.clone()]
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum EntityKind {
+    #[default]
+    Person,
+    School,
+    Clinic,
+    Employer,
+    Organisation,
+    Place,
+}
+
+impl EntityKind {
+    pub const ALL: [EntityKind; 6] = [
+        EntityKind::Person,
+        EntityKind::School,
+        EntityKind::Clinic,
+        EntityKind::Employer,
+        EntityKind::Organisation,
+        EntityKind::Place,
+    ];
+}
+
+/// A label PoIs can share, nested with slashes, e.g. "health/sleep"
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Tag(String);
+
+impl Tag {
+    /// Tidies up what was typed, leaving out empty levels. Nothing is left of a
+    /// tag without any level.
+    pub fn new(path: &str) -> Option<Self> {
+        let levels = path
+            .split('/')
+            .map(|l| l.trim().to_lowercase())
+            .filter(|l| !l.is_empty())
+            .collect::<Vec<_>>();
+        (!levels.is_empty()).then(|| Tag(levels.join("/")))
+    }
+
+    pub fn levels(&self) -> impl Iterator<Item = &str> {
+        self.0.split('/')
+    }
+
+    /// The tag one level up, if nested
+    pub fn parent(&self) -> Option<Tag> {
+        self.0.rsplit_once('/').map(|(p, _)| Tag(p.to_string()))
+    }
+
+    /// Whether it is the other tag, or nested somewhere under it
+    pub fn is_within(&self, other: &Tag) -> bool {
+        self.0 == other.0
+            || self
+                .0
+                .strip_prefix(&other.0)
+                .is_some_and(|rest| rest.starts_with('/'))
+    }
+}
+
+impl std::fmt::Display for Tag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
 pub struct RelationshipQuality;
 pub struct Personality;
 // enum Precision<T> {
@@ -34,6 +254,46 @@ pub struct PointOfInterest {
     ends: RwSignal<Ending>,
     name: RwSignal<String>,
     context: RwSignal<String>,
+    /// Only expected, e.g. generated, until someone confirms it
+    tentative: RwSignal<bool>,
+    recurs: RwSignal<Option<Recurrence>>,
+    /// Not assessed unless given
+    calibration: RwSignal<Option<Calibration>>,
+    /// Where what is told about it comes from
+    sources: RwSignal<Vec<Identifier>>,
+    /// Versions of when it started, at most one per source
+    claims: RwSignal<Vec<Claim>>,
+    /// The source whose claim `starts` follows
+    preferred: RwSignal<Option<Identifier>>,
+    /// When it was held to have happened, as recorded over time, earliest first
+    history: RwSignal<Vec<Revision>>,
+    /// The named entities it mentions
+    entities: RwSignal<Vec<Identifier>>,
+    /// Where it took place, such as where someone lived
+    place: RwSignal<Option<Identifier>>,
+    tags: RwSignal<Vec<Tag>>,
+}
+
+/// What was held about when a PoI happened, from when it was recorded
+#[derive(Debug, Clone, Copy)]
+pub struct Revision {
+    pub recorded: NaiveDateTime,
+    pub starts: PointInTime,
+    pub ends: Ending,
+}
+
+impl Revision {
+    fn at(recorded: NaiveDateTime, starts: PointInTime, ends: Ending) -> Self {
+        Self {
+            recorded,
+            starts,
+            ends,
+        }
+    }
+
+    fn now(starts: PointInTime, ends: Ending) -> Self {
+        Self::at(Local::now().naive_local(), starts, ends)
+    }
 }
 
 impl PointOfInterest {
@@ -41,6 +301,7 @@ impl PointOfInterest {
         Self {
             parent,
             name: RwSignal::new(name),
+            history: vec![Revision::now(PointInTime::default(), Ending::default())].into(),
             ..Default::default()
         }
     }
@@ -56,10 +317,262 @@ impl PointOfInterest {
             timeline,
             name: name.into(),
             starts: starts.into(),
+            history: vec![Revision::now(starts, Ending::default())].into(),
             ..Default::default()
         }
     }
-    // fn starts();
+
+    /// The dates the start of this PoI could fall on, widened by its margin
+    pub fn starts_within(&self, lookup: &impl Lookup) -> Result<Interval, TimeError> {
+        let starts = self.starts_as_stated(lookup)?;
+        match self.calibration().and_then(|c| c.margin) {
+            Some(margin) => Ok(Interval::new(
+                margin
+                    .before(starts.earliest())
+                    .unwrap_or(starts.earliest()),
+                margin.after(starts.latest()).unwrap_or(starts.latest()),
+            )),
+            None => Ok(starts),
+        }
+    }
+
+    /// The dates the start could fall on as it was given, without the margin
+    fn starts_as_stated(&self, lookup: &impl Lookup) -> Result<Interval, TimeError> {
+        self.starts_as_of(lookup.as_of())
+            .resolve_along(lookup, &mut vec![self.id])
+    }
+
+    pub fn starts(&self) -> PointInTime {
+        self.starts.get()
+    }
+
+    pub fn set_starts(&self, starts: PointInTime) {
+        self.starts.set(starts);
+        self.record();
+    }
+
+    /// The dates the end of this PoI could fall on
+    pub fn ends_within(&self, lookup: &impl Lookup) -> Result<Interval, TimeError> {
+        self.ends_as_of(lookup.as_of())
+            .resolve_along(self, lookup, &mut Vec::new())
+    }
+
+    pub fn ends(&self) -> Ending {
+        self.ends.get()
+    }
+
+    pub fn set_ends(&self, ends: Ending) {
+        self.ends.set(ends);
+        self.record();
+    }
+
+    /// Keeps what is now held about when it happened, along with when
+    fn record(&self) {
+        let revision = Revision::now(self.starts.get_untracked(), self.ends.get_untracked());
+        self.history.update(|h| h.push(revision));
+    }
+
+    pub fn history(&self) -> Vec<Revision> {
+        self.history.get()
+    }
+
+    /// What was recorded last by the given time. Without a time, or any
+    /// recorded history, it is what is held now.
+    fn revision_as_of(&self, at: Option<NaiveDateTime>) -> Option<Revision> {
+        let at = at?;
+        self.history
+            .with(|h| h.iter().rev().find(|r| r.recorded <= at).copied())
+    }
+
+    /// When it started, as held at the given time
+    pub fn starts_as_of(&self, at: Option<NaiveDateTime>) -> PointInTime {
+        self.revision_as_of(at)
+            .map_or_else(|| self.starts(), |r| r.starts)
+    }
+
+    /// How it ended, as held at the given time
+    pub fn ends_as_of(&self, at: Option<NaiveDateTime>) -> Ending {
+        self.revision_as_of(at)
+            .map_or_else(|| self.ends(), |r| r.ends)
+    }
+
+    /// Whether it had been recorded at all by the given time
+    pub fn is_recorded_by(&self, at: Option<NaiveDateTime>) -> bool {
+        at.is_none_or(|at| {
+            self.history
+                .with(|h| h.first().is_none_or(|r| r.recorded <= at))
+        })
+    }
+
+    pub fn is_tentative(&self) -> bool {
+        self.tentative.get()
+    }
+
+    pub fn calibration(&self) -> Option<Calibration> {
+        self.calibration.get()
+    }
+
+    pub fn set_calibration(&self, calibration: Option<Calibration>) {
+        self.calibration.set(calibration);
+    }
+
+    /// Whether its date is at least as certain as `least`. One that has not
+    /// been assessed always is.
+    pub fn is_certain_enough(&self, least: Option<Certainty>) -> bool {
+        match (least, self.calibration()) {
+            (Some(least), Some(c)) => c.date >= least,
+            _ => true,
+        }
+    }
+
+    pub fn sources(&self) -> Vec<Identifier> {
+        self.sources.get()
+    }
+
+    /// Traces the PoI back to a source, once
+    pub fn cite(&self, source: Identifier) {
+        self.sources.update(|s| {
+            if !s.contains(&source) {
+                s.push(source)
+            }
+        });
+    }
+
+    pub fn uncite(&self, source: &Identifier) {
+        self.sources.update(|s| s.retain(|s| s != source));
+    }
+
+    pub fn cites(&self, source: &Identifier) -> bool {
+        self.sources.with(|s| s.contains(source))
+    }
+
+    pub fn place(&self) -> Option<Identifier> {
+        self.place.get()
+    }
+
+    pub fn set_place(&self, place: Option<Identifier>) {
+        self.place.set(place);
+    }
+
+    pub fn tags(&self) -> Vec<Tag> {
+        self.tags.get()
+    }
+
+    pub fn tag(&self, tag: Tag) {
+        self.tags.update(|t| {
+            if !t.contains(&tag) {
+                t.push(tag)
+            }
+        });
+    }
+
+    pub fn untag(&self, tag: &Tag) {
+        self.tags.update(|t| t.retain(|t| t != tag));
+    }
+
+    /// Whether any of its tags is the given one, or nested under it
+    pub fn is_tagged(&self, tag: &Tag) -> bool {
+        self.tags.with(|t| t.iter().any(|t| t.is_within(tag)))
+    }
+
+    pub fn entities(&self) -> Vec<Identifier> {
+        self.entities.get()
+    }
+
+    pub fn mention(&self, entity: Identifier) {
+        self.entities.update(|e| {
+            if !e.contains(&entity) {
+                e.push(entity)
+            }
+        });
+    }
+
+    pub fn unmention(&self, entity: &Identifier) {
+        self.entities.update(|e| e.retain(|e| e != entity));
+    }
+
+    pub fn mentions(&self, entity: &Identifier) -> bool {
+        self.entities.with(|e| e.contains(entity))
+    }
+
+    pub fn claims(&self) -> Vec<Claim> {
+        self.claims.get()
+    }
+
+    /// Adds a source's version of when it started, replacing what the source said
+    /// before. The first claim made is preferred until another one is.
+    pub fn claim(&self, claim: Claim) {
+        let source = claim.source;
+        self.claims.update(|c| {
+            c.retain(|c| c.source != source);
+            c.push(claim);
+        });
+        self.cite(source);
+        let preferred = self.preferred.get_untracked();
+        if preferred.is_none_or(|p| p == source) {
+            self.prefer(&source);
+        }
+    }
+
+    pub fn preferred(&self) -> Option<Identifier> {
+        self.preferred.get()
+    }
+
+    /// Goes by what the source said. Sources without a claim are passed over.
+    pub fn prefer(&self, source: &Identifier) {
+        let claim = self
+            .claims
+            .with(|c| c.iter().find(|c| c.source == *source).copied());
+        let Some(claim) = claim else {
+            return;
+        };
+        self.preferred.set(Some(claim.source));
+        self.set_starts(claim.starts);
+    }
+
+    /// Whether two of its claims cannot both be right
+    pub fn is_disputed(&self, lookup: &impl Lookup) -> bool {
+        let told = self.claims.with(|c| {
+            c.iter()
+                .filter_map(|c| c.starts.resolve_along(lookup, &mut vec![self.id]).ok())
+                .collect::<Vec<_>>()
+        });
+        told.iter()
+            .enumerate()
+            .any(|(i, a)| told[..i].iter().any(|b| !a.overlaps(b)))
+    }
+
+    pub fn recurs(&self) -> Option<Recurrence> {
+        self.recurs.get()
+    }
+
+    pub fn set_recurs(&self, recurs: Option<Recurrence>) {
+        self.recurs.set(recurs);
+    }
+
+    /// Each time the PoI happened, from its start to its end, or up to today while
+    /// it has not ended. One that does not recur happened once.
+    pub fn occurrences(
+        &self,
+        lookup: &impl Lookup,
+    ) -> Result<Box<dyn Iterator<Item = Interval>>, TimeError> {
+        // Felmarginalen gäller när den började, inte hur ofta den återkom
+        let starts = self.starts_as_stated(lookup)?;
+        let Some(recurs) = self.recurs() else {
+            return Ok(Box::new(std::iter::once(starts)));
+        };
+        let ends = match self.ends_within(lookup) {
+            Ok(ends) => ends.latest(),
+            Err(TimeError::Undetermined) => lookup.today().max(starts.latest()),
+            Err(e) => return Err(e),
+        };
+        let bounds = Interval::new(starts.earliest(), ends);
+        Ok(recurs.occurrences(bounds, lookup.seasons()))
+    }
+
+    pub fn confirm(&self) {
+        self.tentative.set(false);
+    }
     // fn duration();
     // fn identity();
     // fn title();
@@ -77,157 +590,1252 @@ impl LevelUp for PointOfInterest {
     }
 }
 
-impl Identify for PointOfInterest {
-    fn identity(&self) -> Identifier {
-        self.id
+impl Identify for PointOfInterest {
+    fn identity(&self) -> Identifier {
+        self.id
+    }
+}
+
+impl HasBeginning for PointOfInterest {
+    fn begins(&self) -> Result<NaiveDate, TimeError> {
+        self.starts.get().begins()
+    }
+}
+impl MyName for PointOfInterest {
+    fn name(&self) -> MaybeSignal<String> {
+        self.name.into()
+    }
+}
+
+impl PointOfInterest {
+    /// What the PoI can be searched by. Its dates resolve through the lookup, so the
+    /// keywords change with whatever the PoI is dated relative to.
+    pub fn keywords(&self, lookup: &impl Lookup) -> Vec<String> {
+        let mut strings = vec![self.name.get()];
+        // Och på dem som nämns, som "BVC Räveby"
+        strings.extend(
+            self.entities
+                .get()
+                .iter()
+                .filter_map(|e| lookup.entity(e))
+                .map(|e| e.name().get()),
+        );
+        // Och på varje nivå av taggarna
+        for tag in self.tags.get() {
+            strings.extend(tag.levels().map(String::from));
+        }
+        // Gör det möjligt att söka på årtal, även för osäkra datum
+        if let Ok(within) = self.starts_within(lookup) {
+            strings
+                .extend((within.earliest().year()..=within.latest().year()).map(|y| y.to_string()));
+        }
+        strings
+    }
+}
+
+// TRAITS
+
+pub trait LevelUp {
+    fn parent(&self) -> Identifier;
+}
+
+pub trait HasBeginning {
+    /// The earliest date it could have begun, or why that cannot be told
+    fn begins(&self) -> Result<NaiveDate, TimeError>;
+    fn try_begins(&self) -> Option<NaiveDate> {
+        self.begins().ok()
+    }
+}
+
+pub trait MyName {
+    fn name(&self) -> MaybeSignal<String>;
+}
+
+pub trait Identify {
+    fn identity(&self) -> Identifier;
+}
+
+/// What a point in time may need to look up in order to be resolved
+pub trait Lookup {
+    fn person(&self, id: &Identifier) -> Option<Person>;
+    fn poi(&self, id: &Identifier) -> Option<PointOfInterest>;
+    /// When the person whose timeline it is was born
+    fn origo(&self) -> Option<Interval>;
+    /// The day dates left open run up to: the day the timeline is seen as of, or
+    /// else today
+    fn today(&self) -> NaiveDate {
+        self.as_of()
+            .map_or_else(|| Local::now().date_naive(), |at| at.date())
+    }
+
+    /// A source PoIs can be traced back to
+    fn source(&self, _id: &Identifier) -> Option<Source> {
+        None
+    }
+
+    /// Someone or something a PoI mentions
+    fn entity(&self, _id: &Identifier) -> Option<NamedEntity> {
+        None
+    }
+
+    /// Where a PoI took place
+    fn place(&self, _id: &Identifier) -> Option<Place> {
+        None
+    }
+
+    /// The time the timeline is seen as it was recorded by, if not now
+    fn as_of(&self) -> Option<NaiveDateTime> {
+        None
+    }
+
+    /// How the timeline counts its seasons
+    fn seasons(&self) -> SeasonModel {
+        SeasonModel::default()
+    }
+}
+
+/// Nothing to look up, only points in time that stand on their own resolve
+impl Lookup for () {
+    fn person(&self, _id: &Identifier) -> Option<Person> {
+        None
+    }
+
+    fn poi(&self, _id: &Identifier) -> Option<PointOfInterest> {
+        None
+    }
+
+    fn origo(&self) -> Option<Interval> {
+        None
+    }
+}
+
+#[derive(Default, Debug, Clone, Copy)]
+pub enum Ending {
+    At(PointInTime),
+    After(Duration),
+    /// When the linked PoI starts
+    Upon(Identifier),
+    /// Explicitly left open, so that no end is inferred
+    Ongoing,
+    #[default]
+    Undetermined,
+}
+
+impl Ending {
+    /// The dates the ending could fall on, when ending the given PoI
+    pub fn resolve(
+        &self,
+        poi: &PointOfInterest,
+        lookup: &impl Lookup,
+    ) -> Result<Interval, TimeError> {
+        self.resolve_along(poi, lookup, &mut Vec::new())
+    }
+
+    fn resolve_along(
+        &self,
+        poi: &PointOfInterest,
+        lookup: &impl Lookup,
+        trail: &mut Vec<Identifier>,
+    ) -> Result<Interval, TimeError> {
+        match self {
+            Ending::At(p) => p.resolve_along(lookup, trail),
+            Ending::After(d) => Offset::After(*d)
+                .apply(starts_of(poi, lookup, trail)?, &lookup.seasons())
+                .ok_or(TimeError::OutOfRange),
+            Ending::Upon(next) => anchored(next, lookup, trail),
+            Ending::Ongoing | Ending::Undetermined => Err(TimeError::Undetermined),
+        }
+    }
+}
+
+/// Somewhere lived, from the street address out to the country. Any level may be
+/// left out.
+#[derive(Default, Debug, Clone, PartialEq)]
+pub struct Place {
+    id: Identifier,
+    address: Option<String>,
+    town: Option<String>,
+    municipality: Option<String>,
+    country: Option<String>,
+    coordinates: Option<Coordinates>,
+}
+
+impl Place {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn at_address(mut self, address: String) -> Self {
+        self.address = Some(address);
+        self
+    }
+
+    pub fn in_town(mut self, town: String) -> Self {
+        self.town = Some(town);
+        self
+    }
+
+    pub fn in_municipality(mut self, municipality: String) -> Self {
+        self.municipality = Some(municipality);
+        self
+    }
+
+    pub fn in_country(mut self, country: String) -> Self {
+        self.country = Some(country);
+        self
+    }
+
+    pub fn located(mut self, coordinates: Coordinates) -> Self {
+        self.coordinates = Some(coordinates);
+        self
+    }
+
+    pub fn town(&self) -> Option<&String> {
+        self.town.as_ref()
+    }
+
+    pub fn coordinates(&self) -> Option<Coordinates> {
+        self.coordinates
+    }
+
+    /// From the most to the least specific, as far as known
+    pub fn levels(&self) -> impl Iterator<Item = &String> {
+        [&self.address, &self.town, &self.municipality, &self.country]
+            .into_iter()
+            .flatten()
+    }
+}
+
+impl Identify for Place {
+    fn identity(&self) -> Identifier {
+        self.id
+    }
+}
+
+impl MyName for Place {
+    /// The most specific level known
+    fn name(&self) -> MaybeSignal<String> {
+        self.levels().next().cloned().unwrap_or_default().into()
+    }
+}
+
+/// In decimal degrees
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+}
+
+// Samma höst (eller annan upplösning) som ...
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum PointInTime {
+    Approximated(Approximated),
+    RelativeTo(Identifier, Offset), // PoI och hur långt ifrån dess början
+    ChronologicalAge(i8, Identifier), // ålder och person
+    TimeAgo {
+        at: NaiveDate, // när det sades, t.ex. datum för intervjun
+        time: Duration,
+    },
+    Time(NaiveDateTime),
+    Day(NaiveDate),
+    /// An ISO week, by the year it is counted in and its number
+    Week(i32, u32),
+    /// From the start of one point in time to the end of another, each kept as
+    /// it was told
+    Range(RangeEnd, RangeEnd),
+    Month(i32, Month),
+    Season(i32, Season),
+    /// A term of a grade, see [`PointInTime::Grade`]
+    TermOf(Term, u8, Identifier),
+    Year(i32),
+    /// The school year of a grade for a person. 0 is förskoleklass, 10 to 12 the
+    /// years of gymnasiet.
+    Grade(u8, Identifier),
+    Stadium(Stadium, Identifier),
+    // Period,
+    #[default]
+    Undetermined,
+}
+
+impl HasBeginning for PointInTime {
+    fn begins(&self) -> Result<NaiveDate, TimeError> {
+        self.resolve(&()).map(|i| i.earliest())
+    }
+}
+
+impl PointInTime {
+    /// The earliest and latest date this point in time could refer to,
+    /// given its precision. A `Year` covers the whole year, a `Day` only itself.
+    pub fn resolve(&self, lookup: &impl Lookup) -> Result<Interval, TimeError> {
+        self.resolve_along(lookup, &mut Vec::new())
+    }
+
+    /// Resolves while keeping track of the PoIs passed through on the way,
+    /// so that anchors which eventually refer back to themselves are caught.
+    fn resolve_along(
+        &self,
+        lookup: &impl Lookup,
+        trail: &mut Vec<Identifier>,
+    ) -> Result<Interval, TimeError> {
+        match self {
+            PointInTime::Time(r) => Ok(Interval::day(r.date())),
+            PointInTime::Day(d) => Ok(Interval::day(*d)),
+            PointInTime::Week(y, w) => {
+                let monday = NaiveDate::from_isoywd_opt(*y, *w, Weekday::Mon);
+                let sunday = NaiveDate::from_isoywd_opt(*y, *w, Weekday::Sun);
+                monday
+                    .zip(sunday)
+                    .map(|(m, s)| Interval::new(m, s))
+                    .ok_or(TimeError::InvalidWeek(*y, *w))
+            }
+            PointInTime::Range(from, to) => {
+                let from = PointInTime::from(*from).resolve_along(lookup, trail)?;
+                let to = PointInTime::from(*to).resolve_along(lookup, trail)?;
+                if to.latest() < from.earliest() {
+                    return Err(TimeError::ReversedRange);
+                }
+                Ok(Interval::new(from.earliest(), to.latest()))
+            }
+            PointInTime::Month(y, m) => {
+                let first =
+                    NaiveDate::from_ymd_opt(*y, *m as u32, 1).ok_or(TimeError::OutOfRange)?;
+                Ok(Interval::months(first, 1))
+            }
+            PointInTime::Season(y, s) => {
+                s.during(*y, &lookup.seasons()).ok_or(TimeError::OutOfRange)
+            }
+            PointInTime::Year(y) => {
+                let first = NaiveDate::from_ymd_opt(*y, 1, 1).ok_or(TimeError::OutOfRange)?;
+                Ok(Interval::months(first, 12))
+            }
+            // Från första möjliga födelsedag till dagen före nästa
+            PointInTime::ChronologicalAge(age, person) => {
+                let born = lookup
+                    .person(person)
+                    .ok_or(TimeError::UnknownPerson(*person))?
+                    .born()?;
+                let earliest = born.earliest().shift_years(*age as i32);
+                let latest = born
+                    .latest()
+                    .shift_years(*age as i32 + 1)
+                    .and_then(|d| d.pred_opt());
+                earliest
+                    .zip(latest)
+                    .map(|(e, l)| Interval::new(e, l))
+                    .ok_or(TimeError::OutOfRange)
+            }
+            // "Tre år sedan" är avrundat, så perioden sträcker sig ett halvt år åt vardera håll
+            PointInTime::TimeAgo { at, time } => {
+                let then = time.before(*at).ok_or(TimeError::OutOfRange)?;
+                let slack = time.slack();
+                let earliest = slack.before(then).ok_or(TimeError::OutOfRange)?;
+                let latest = slack.after(then).unwrap_or(*at).min(*at);
+                Ok(Interval::new(earliest, latest))
+            }
+            PointInTime::RelativeTo(anchor, offset) => offset
+                .apply(anchored(anchor, lookup, trail)?, &lookup.seasons())
+                .ok_or(TimeError::OutOfRange),
+            PointInTime::Approximated(a) => a.narrow(lookup, trail),
+            // Från det tidigaste möjliga födelseårets skolstart till det senastes
+            PointInTime::Grade(grade, person) => {
+                let (first, last) = school_start(person, lookup)?;
+                let years = [first, last].map(|y| school_year(y + *grade as i32));
+                years[0]
+                    .zip(years[1])
+                    .map(|(f, l)| f.hull(&l))
+                    .ok_or(TimeError::OutOfRange)
+            }
+            PointInTime::TermOf(term, grade, person) => {
+                let (first, last) = school_start(person, lookup)?;
+                let terms = [first, last].map(|y| term.during(y + *grade as i32));
+                terms[0]
+                    .zip(terms[1])
+                    .map(|(f, l)| f.hull(&l))
+                    .ok_or(TimeError::OutOfRange)
+            }
+            PointInTime::Stadium(stadium, person) => {
+                let (first, last) = school_start(person, lookup)?;
+                let Some(grades) = stadium.grades() else {
+                    // Förskolan från ettårsdagen fram till förskoleklass
+                    let born = lookup
+                        .person(person)
+                        .ok_or(TimeError::UnknownPerson(*person))?
+                        .born()?;
+                    let starts = born.earliest().shift_years(1);
+                    let ends = school_year(last).and_then(|y| y.earliest().pred_opt());
+                    return starts
+                        .zip(ends)
+                        .map(|(s, e)| Interval::new(s, e.max(s)))
+                        .ok_or(TimeError::OutOfRange);
+                };
+                let starts = school_year(first + *grades.start() as i32);
+                let ends = school_year(last + *grades.end() as i32);
+                starts
+                    .zip(ends)
+                    .map(|(s, e)| s.hull(&e))
+                    .ok_or(TimeError::OutOfRange)
+            }
+            PointInTime::Undetermined => Err(TimeError::Undetermined),
+        }
+    }
+}
+
+/// Resolves the start of another PoI, unless it has already been passed through
+fn anchored(
+    anchor: &Identifier,
+    lookup: &impl Lookup,
+    trail: &mut Vec<Identifier>,
+) -> Result<Interval, TimeError> {
+    let poi = lookup.poi(anchor).ok_or(TimeError::UnknownPoI(*anchor))?;
+    starts_of(&poi, lookup, trail)
+}
+
+fn starts_of(
+    poi: &PointOfInterest,
+    lookup: &impl Lookup,
+    trail: &mut Vec<Identifier>,
+) -> Result<Interval, TimeError> {
+    if trail.contains(&poi.id) {
+        return Err(TimeError::Cycle(poi.id));
+    }
+    trail.push(poi.id);
+    let starts = poi
+        .starts_as_of(lookup.as_of())
+        .resolve_along(lookup, trail);
+    trail.pop();
+    starts
+}
+
+impl PointInTime {
+    pub fn ago(time: Duration, at: NaiveDate) -> Self {
+        PointInTime::TimeAgo { at, time }
+    }
+
+    pub fn relative_to(anchor: &PointOfInterest, offset: Offset) -> Self {
+        PointInTime::RelativeTo(anchor.identity(), offset)
+    }
+
+    /// The stage of school it is given in, if any. Ages before the year of
+    /// förskoleklass are förskolan.
+    pub fn stage(&self) -> Option<Stadium> {
+        match self {
+            PointInTime::Stadium(stadium, _) => Some(*stadium),
+            PointInTime::Grade(grade, _) | PointInTime::TermOf(_, grade, _) => {
+                Stadium::of_grade(*grade)
+            }
+            PointInTime::ChronologicalAge(1..=5, _) => Some(Stadium::Preschool),
+            _ => None,
+        }
+    }
+
+    pub fn day(year: i32, month: u32, day: u32) -> Result<Self, TimeError> {
+        Self::try_from((year, month, day))
+    }
+
+    pub fn month(year: i32, month: u8) -> Result<Self, TimeError> {
+        Self::try_from((year, month))
+    }
+
+    /// An ISO week, checked to be one the year has
+    pub fn week(year: i32, week: u32) -> Result<Self, TimeError> {
+        NaiveDate::from_isoywd_opt(year, week, Weekday::Mon)
+            .map(|_| PointInTime::Week(year, week))
+            .ok_or(TimeError::InvalidWeek(year, week))
+    }
+
+    /// Whether it is given relative to something, so that the dates it comes
+    /// down to are not evident from how it reads
+    pub fn is_relative(&self) -> bool {
+        match self {
+            PointInTime::Range(from, to) => {
+                PointInTime::from(*from).is_relative() || PointInTime::from(*to).is_relative()
+            }
+            _ => matches!(
+                self,
+                PointInTime::TimeAgo { .. }
+                    | PointInTime::RelativeTo(..)
+                    | PointInTime::ChronologicalAge(..)
+                    | PointInTime::Approximated(_)
+                    | PointInTime::Grade(..)
+                    | PointInTime::TermOf(..)
+                    | PointInTime::Stadium(..)
+            ),
+        }
+    }
+}
+
+/// One end of a [`PointInTime::Range`]. Anything that stands on its own or
+/// only refers to the person the timeline is about.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RangeEnd {
+    Day(NaiveDate),
+    Week(i32, u32),
+    Month(i32, Month),
+    Season(i32, Season),
+    Year(i32),
+    TimeAgo { at: NaiveDate, time: Duration },
+    ChronologicalAge(i8, Identifier),
+    Grade(u8, Identifier),
+    TermOf(Term, u8, Identifier),
+    Stadium(Stadium, Identifier),
+}
+
+impl From<RangeEnd> for PointInTime {
+    fn from(end: RangeEnd) -> Self {
+        match end {
+            RangeEnd::Day(d) => PointInTime::Day(d),
+            RangeEnd::Week(y, w) => PointInTime::Week(y, w),
+            RangeEnd::Month(y, m) => PointInTime::Month(y, m),
+            RangeEnd::Season(y, s) => PointInTime::Season(y, s),
+            RangeEnd::Year(y) => PointInTime::Year(y),
+            RangeEnd::TimeAgo { at, time } => PointInTime::TimeAgo { at, time },
+            RangeEnd::ChronologicalAge(a, p) => PointInTime::ChronologicalAge(a, p),
+            RangeEnd::Grade(g, p) => PointInTime::Grade(g, p),
+            RangeEnd::TermOf(t, g, p) => PointInTime::TermOf(t, g, p),
+            RangeEnd::Stadium(s, p) => PointInTime::Stadium(s, p),
+        }
+    }
+}
+
+impl TryFrom<PointInTime> for RangeEnd {
+    type Error = PointInTime;
+
+    /// Gives the point in time back if it cannot be an end of a range
+    fn try_from(point: PointInTime) -> Result<Self, Self::Error> {
+        match point {
+            PointInTime::Day(d) => Ok(RangeEnd::Day(d)),
+            PointInTime::Week(y, w) => Ok(RangeEnd::Week(y, w)),
+            PointInTime::Month(y, m) => Ok(RangeEnd::Month(y, m)),
+            PointInTime::Season(y, s) => Ok(RangeEnd::Season(y, s)),
+            PointInTime::Year(y) => Ok(RangeEnd::Year(y)),
+            PointInTime::TimeAgo { at, time } => Ok(RangeEnd::TimeAgo { at, time }),
+            PointInTime::ChronologicalAge(a, p) => Ok(RangeEnd::ChronologicalAge(a, p)),
+            PointInTime::Grade(g, p) => Ok(RangeEnd::Grade(g, p)),
+            PointInTime::TermOf(t, g, p) => Ok(RangeEnd::TermOf(t, g, p)),
+            PointInTime::Stadium(s, p) => Ok(RangeEnd::Stadium(s, p)),
+            other => Err(other),
+        }
+    }
+}
+
+/// The autumns a person started förskoleklass, for the earliest and the latest
+/// year they may have been born. Children start the year they turn six.
+fn school_start(person: &Identifier, lookup: &impl Lookup) -> Result<(i32, i32), TimeError> {
+    let person = lookup
+        .person(person)
+        .ok_or(TimeError::UnknownPerson(*person))?;
+    let born = person.born()?;
+    let turns_six = 6 + person.school_start() as i32;
+    Ok((
+        born.earliest().year() + turns_six,
+        born.latest().year() + turns_six,
+    ))
+}
+
+/// The school year that starts in the autumn of the given year
+fn school_year(autumn: i32) -> Option<Interval> {
+    let fall = Term::Fall.during(autumn)?;
+    Some(fall.hull(&Term::Spring.during(autumn)?))
+}
+
+/// Why a point in time could not be resolved to dates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TimeError {
+    /// Nothing is known about when
+    Undetermined,
+    /// Refers to a person that is not known in this timeline
+    UnknownPerson(Identifier),
+    /// Falls outside of what the calendar can represent
+    OutOfRange,
+    /// Given relative to a PoI that is not known in this timeline
+    UnknownPoI(Identifier),
+    /// Given relative to a PoI that, in turn, is given relative to the first
+    Cycle(Identifier),
+    /// The constraints given only bound it on one side, and there is no birth
+    /// date to bound the other
+    Unbounded,
+    /// No date satisfies both constraints
+    Contradiction(Constraint, Constraint),
+    /// A month number outside of 1 to 12
+    InvalidMonth(u8),
+    /// A year, month and day that do not make a date
+    InvalidDate(i32, u32, u32),
+    /// A week number the year does not have
+    InvalidWeek(i32, u32),
+    /// A range that ends before it starts
+    ReversedRange,
+}
+
+impl std::fmt::Display for TimeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeError::Undetermined => write!(f, "No time given"),
+            TimeError::UnknownPerson(id) => {
+                write!(f, "Refers to an unknown person ({})", id)
+            }
+            TimeError::OutOfRange => write!(f, "Not a valid date"),
+            TimeError::UnknownPoI(id) => {
+                write!(f, "Relative to an unknown event ({})", id)
+            }
+            TimeError::Cycle(id) => {
+                write!(f, "Relative to itself through ({})", id)
+            }
+            TimeError::Unbounded => write!(
+                f,
+                "The constraints only bound it on one side, and no birth date bounds the other"
+            ),
+            TimeError::Contradiction(a, b) => {
+                write!(f, "Cannot be both {a} and {b} what was given")
+            }
+            TimeError::InvalidMonth(m) => write!(f, "There is no month {m}"),
+            TimeError::InvalidDate(y, m, d) => write!(f, "{y}-{m:02}-{d:02} is not a date"),
+            TimeError::InvalidWeek(y, w) => write!(f, "{y} has no week {w}"),
+            TimeError::ReversedRange => write!(f, "Ends before it starts"),
+        }
+    }
+}
+
+trait ShiftYears: Sized {
+    fn shift_years(self, years: i32) -> Option<Self>;
+}
+
+impl ShiftYears for NaiveDate {
+    /// Same day and month, `years` later (or earlier). 29 February lands on 28 February.
+    fn shift_years(self, years: i32) -> Option<Self> {
+        let months = Months::new(years.unsigned_abs() * 12);
+        if years < 0 {
+            self.checked_sub_months(months)
+        } else {
+            self.checked_add_months(months)
+        }
+    }
+}
+
+impl From<NaiveDate> for PointInTime {
+    fn from(value: NaiveDate) -> Self {
+        PointInTime::Day(value)
+    }
+}
+
+impl TryFrom<(i32, u8)> for PointInTime {
+    type Error = TimeError;
+
+    fn try_from((y, m): (i32, u8)) -> Result<Self, Self::Error> {
+        Ok(PointInTime::Month(y, Month::try_from(m)?))
+    }
+}
+
+impl TryFrom<(i32, u32, u32)> for PointInTime {
+    type Error = TimeError;
+
+    fn try_from((y, m, d): (i32, u32, u32)) -> Result<Self, Self::Error> {
+        NaiveDate::from_ymd_opt(y, m, d)
+            .map(PointInTime::Day)
+            .ok_or(TimeError::InvalidDate(y, m, d))
+    }
+}
+
+impl From<IsoWeek> for PointInTime {
+    fn from(week: IsoWeek) -> Self {
+        PointInTime::Week(week.year(), week.week())
+    }
+}
+
+impl From<(NaiveDate, NaiveDate)> for PointInTime {
+    fn from((from, to): (NaiveDate, NaiveDate)) -> Self {
+        PointInTime::Range(RangeEnd::Day(from), RangeEnd::Day(to))
+    }
+}
+
+impl From<Interval> for PointInTime {
+    fn from(range: Interval) -> Self {
+        PointInTime::from((range.earliest(), range.latest()))
+    }
+}
+
+impl From<i32> for PointInTime {
+    fn from(y: i32) -> Self {
+        PointInTime::Year(y)
+    }
+}
+
+/// A stretch of days, both ends included, that something is known to lie within.
+/// Orders by earliest date first, so that the more certain of two equally early
+/// intervals comes first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Interval {
+    earliest: NaiveDate,
+    latest: NaiveDate,
+}
+
+impl Interval {
+    pub fn new(a: NaiveDate, b: NaiveDate) -> Self {
+        Self {
+            earliest: a.min(b),
+            latest: a.max(b),
+        }
+    }
+
+    pub fn day(d: NaiveDate) -> Self {
+        Self::new(d, d)
+    }
+
+    /// Whole calendar months starting at `first`
+    pub fn months(first: NaiveDate, months: u32) -> Self {
+        let after = first
+            .checked_add_months(Months::new(months))
+            .unwrap_or(NaiveDate::MAX);
+        Self::new(first, after.pred_opt().unwrap_or(first))
+    }
+
+    pub fn earliest(&self) -> NaiveDate {
+        self.earliest
+    }
+
+    pub fn latest(&self) -> NaiveDate {
+        self.latest
+    }
+
+    /// Number of days covered, counting both ends
+    pub fn days(&self) -> i64 {
+        (self.latest - self.earliest).num_days() + 1
+    }
+
+    /// True when the interval is a single known day
+    pub fn is_exact(&self) -> bool {
+        self.earliest == self.latest
+    }
+
+    pub fn contains(&self, d: NaiveDate) -> bool {
+        self.earliest <= d && d <= self.latest
+    }
+
+    pub fn overlaps(&self, other: &Interval) -> bool {
+        self.earliest <= other.latest && other.earliest <= self.latest
+    }
+
+    /// The days both cover, if any
+    pub fn intersection(&self, other: &Interval) -> Option<Interval> {
+        self.overlaps(other).then(|| Interval {
+            earliest: self.earliest.max(other.earliest),
+            latest: self.latest.min(other.latest),
+        })
+    }
+
+    /// The smallest interval covering both
+    pub fn hull(&self, other: &Interval) -> Interval {
+        Interval {
+            earliest: self.earliest.min(other.earliest),
+            latest: self.latest.max(other.latest),
+        }
+    }
+}
+
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_exact() {
+            write!(f, "{}", self.earliest)
+        } else {
+            write!(f, "{} – {}", self.earliest, self.latest)
+        }
+    }
+}
+
+/// An amount of calendar time, in the unit it was given in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Duration {
+    Days(u32),
+    Weeks(u32),
+    Months(u32),
+    Years(u32),
+}
+
+impl Duration {
+    pub fn is_zero(&self) -> bool {
+        matches!(
+            self,
+            Duration::Days(0) | Duration::Weeks(0) | Duration::Months(0) | Duration::Years(0)
+        )
+    }
+
+    pub fn before(&self, d: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Duration::Days(n) => d.checked_sub_days(Days::new(*n as u64)),
+            Duration::Weeks(n) => d.checked_sub_days(Days::new(*n as u64 * 7)),
+            Duration::Months(n) => d.checked_sub_months(Months::new(*n)),
+            Duration::Years(n) => d.checked_sub_months(Months::new(n * 12)),
+        }
+    }
+
+    pub fn after(&self, d: NaiveDate) -> Option<NaiveDate> {
+        match self {
+            Duration::Days(n) => d.checked_add_days(Days::new(*n as u64)),
+            Duration::Weeks(n) => d.checked_add_days(Days::new(*n as u64 * 7)),
+            Duration::Months(n) => d.checked_add_months(Months::new(*n)),
+            Duration::Years(n) => d.checked_add_months(Months::new(n * 12)),
+        }
+    }
+
+    /// The amount repeated `n` times
+    pub fn times(&self, n: u32) -> Option<Duration> {
+        Some(match self {
+            Duration::Days(d) => Duration::Days(d.checked_mul(n)?),
+            Duration::Weeks(w) => Duration::Weeks(w.checked_mul(n)?),
+            Duration::Months(m) => Duration::Months(m.checked_mul(n)?),
+            Duration::Years(y) => Duration::Years(y.checked_mul(n)?),
+        })
+    }
+
+    /// How far off an amount given in this unit can be, half of the unit
+    pub fn slack(&self) -> Duration {
+        match self {
+            Duration::Days(_) => Duration::Days(0),
+            Duration::Weeks(_) => Duration::Days(3),
+            Duration::Months(_) => Duration::Days(15),
+            Duration::Years(_) => Duration::Months(6),
+        }
+    }
+}
+
+impl std::fmt::Display for Duration {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let (n, unit) = match self {
+            Duration::Days(n) => (n, "day"),
+            Duration::Weeks(n) => (n, "week"),
+            Duration::Months(n) => (n, "month"),
+            Duration::Years(n) => (n, "year"),
+        };
+        let plural = if *n == 1 { "" } else { "s" };
+        write!(f, "{n} {unit}{plural}")
+    }
+}
+
+/// How something that happened again and again recurred, between the start and
+/// the end of its PoI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Recurrence {
+    /// Every such season, e.g. every summer
+    EverySeason(Season),
+    /// Once every so often, counted from the first time, e.g. weekly
+    Every(Duration),
+}
+
+impl Recurrence {
+    /// Each time it happened within the bounds, worked out one at a time as needed
+    pub fn occurrences(
+        self,
+        bounds: Interval,
+        seasons: SeasonModel,
+    ) -> Box<dyn Iterator<Item = Interval>> {
+        let (first, last) = (bounds.earliest(), bounds.latest());
+        match self {
+            // En vinter kan räknas till året före eller efter, men bara den del av
+            // säsongen som faller inom perioden hör hit
+            Recurrence::EverySeason(s) => Box::new(
+                (first.year() - 1..=last.year() + 1)
+                    .filter_map(move |y| s.during(y, &seasons))
+                    .filter_map(move |i| i.intersection(&bounds)),
+            ),
+            // Räknat från första gången, så att månadsvis inte glider mot kortare månaders slut
+            Recurrence::Every(d) => Box::new(
+                (0..)
+                    .map_while(move |n| d.times(n)?.after(first))
+                    .take_while(move |day| *day <= last)
+                    .enumerate()
+                    .take_while(move |(n, day)| *n == 0 || *day > first)
+                    .map(|(_, day)| Interval::day(day)),
+            ),
+        }
+    }
+}
+
+/// Where something lies in relation to the start of another PoI
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Offset {
+    After(Duration),
+    Before(Duration),
+    /// The closest such season that has ended before the start
+    SeasonBefore(Season),
+    /// The closest such season that begins after the start
+    SeasonAfter(Season),
+}
+
+impl Offset {
+    /// Moves each end of the anchor's interval. Amounts are taken to be as rounded
+    /// as when said about the past, see [`Duration::slack`].
+    pub fn apply(&self, anchor: Interval, seasons: &SeasonModel) -> Option<Interval> {
+        match self {
+            Offset::After(d) => Some(Interval::new(
+                d.slack().before(d.after(anchor.earliest)?)?,
+                d.slack().after(d.after(anchor.latest)?)?,
+            )),
+            Offset::Before(d) => Some(Interval::new(
+                d.slack().before(d.before(anchor.earliest)?)?,
+                d.slack().after(d.before(anchor.latest)?)?,
+            )),
+            Offset::SeasonBefore(s) => Some(
+                s.before(anchor.earliest, seasons)?
+                    .hull(&s.before(anchor.latest, seasons)?),
+            ),
+            Offset::SeasonAfter(s) => Some(
+                s.after(anchor.earliest, seasons)?
+                    .hull(&s.after(anchor.latest, seasons)?),
+            ),
+        }
+    }
+
+    /// The offset that leads back to the anchor, where there is a simple one
+    pub fn inverse(&self) -> Option<Offset> {
+        match self {
+            Offset::After(d) => Some(Offset::Before(*d)),
+            Offset::Before(d) => Some(Offset::After(*d)),
+            Offset::SeasonBefore(_) | Offset::SeasonAfter(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for Offset {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Offset::After(d) => write!(f, "{d} after"),
+            Offset::Before(d) => write!(f, "{d} before"),
+            Offset::SeasonBefore(s) => write!(f, "the {s} before"),
+            Offset::SeasonAfter(s) => write!(f, "the {s} after"),
+        }
+    }
+}
+
+/// Something a point in time can be held up against
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bound {
+    /// When the PoI started
+    PoI(Identifier),
+    Dates(Interval),
+}
+
+impl Bound {
+    fn resolve_along(
+        &self,
+        lookup: &impl Lookup,
+        trail: &mut Vec<Identifier>,
+    ) -> Result<Interval, TimeError> {
+        match self {
+            Bound::PoI(id) => anchored(id, lookup, trail),
+            Bound::Dates(i) => Ok(*i),
+        }
+    }
+
+    /// From the start of a PoI to its end, as far as the end is known
+    fn span_along(
+        &self,
+        lookup: &impl Lookup,
+        trail: &mut Vec<Identifier>,
+    ) -> Result<Interval, TimeError> {
+        let starts = self.resolve_along(lookup, trail)?;
+        let Bound::PoI(id) = self else {
+            return Ok(starts);
+        };
+        let poi = lookup.poi(id).ok_or(TimeError::UnknownPoI(*id))?;
+        match poi
+            .ends_as_of(lookup.as_of())
+            .resolve_along(&poi, lookup, trail)
+        {
+            Ok(ends) => Ok(starts.hull(&ends)),
+            Err(TimeError::Undetermined) => Ok(starts),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Constraint {
+    After,
+    Before,
+    During,
+}
+
+impl std::fmt::Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let word = match self {
+            Constraint::After => "after",
+            Constraint::Before => "before",
+            Constraint::During => "during",
+        };
+        write!(f, "{word}")
+    }
+}
+
+/// Known only through what it happened after, before or during
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub struct Approximated {
+    after: Option<Bound>,
+    before: Option<Bound>,
+    during: Option<Bound>,
+}
+
+impl Approximated {
+    pub fn after(mut self, bound: Bound) -> Self {
+        self.after = Some(bound);
+        self
+    }
+
+    pub fn before(mut self, bound: Bound) -> Self {
+        self.before = Some(bound);
+        self
+    }
+
+    pub fn during(mut self, bound: Bound) -> Self {
+        self.during = Some(bound);
+        self
     }
-}
 
-impl HasBeginning for PointOfInterest {
-    fn begins(&self) -> NaiveDate {
-        self.starts.get().begins()
+    pub fn constraints(&self) -> impl Iterator<Item = (Constraint, Bound)> {
+        [
+            (Constraint::After, self.after),
+            (Constraint::Before, self.before),
+            (Constraint::During, self.during),
+        ]
+        .into_iter()
+        .filter_map(|(c, b)| b.map(|b| (c, b)))
     }
 
-    fn try_begins(&self) -> Option<NaiveDate> {
-        Some(self.begins())
+    /// The dates that satisfy every constraint. Something after X can at earliest be
+    /// the day after X's earliest possible date, and so on. A side left open is
+    /// bounded by the person's birth or by the lookup's today.
+    fn narrow(
+        &self,
+        lookup: &impl Lookup,
+        trail: &mut Vec<Identifier>,
+    ) -> Result<Interval, TimeError> {
+        let mut lower: Option<(NaiveDate, Constraint)> = None;
+        let mut upper: Option<(NaiveDate, Constraint)> = None;
+        let mut any = false;
+        for (constraint, bound) in self.constraints() {
+            any = true;
+            let i = match constraint {
+                Constraint::During => bound.span_along(lookup, trail)?,
+                _ => bound.resolve_along(lookup, trail)?,
+            };
+            let (from, to) = match constraint {
+                Constraint::After => (i.earliest.succ_opt(), None),
+                Constraint::Before => (None, i.latest.pred_opt()),
+                Constraint::During => (Some(i.earliest), Some(i.latest)),
+            };
+            if let Some(from) = from.filter(|d| lower.is_none_or(|(l, _)| *d > l)) {
+                lower = Some((from, constraint));
+            }
+            if let Some(to) = to.filter(|d| upper.is_none_or(|(u, _)| *d < u)) {
+                upper = Some((to, constraint));
+            }
+        }
+        if !any {
+            return Err(TimeError::Undetermined);
+        }
+        match (lower, upper) {
+            (Some((l, a)), Some((u, b))) if l > u => Err(TimeError::Contradiction(a, b)),
+            (Some((l, _)), Some((u, _))) => Ok(Interval::new(l, u)),
+            (Some((l, _)), None) => Ok(Interval::new(l, lookup.today().max(l))),
+            (None, Some((u, _))) => {
+                let born = lookup.origo().ok_or(TimeError::Unbounded)?;
+                Ok(Interval::new(born.earliest.min(u), u))
+            }
+            (None, None) => Err(TimeError::Unbounded),
+        }
     }
 }
-impl MyName for PointOfInterest {
-    fn name(&self) -> MaybeSignal<String> {
-        self.name.into()
-    }
+
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Season {
+    Winter,
+    Spring,
+    #[default]
+    Summer,
+    Fall,
 }
 
-impl Indexable for PointOfInterest {
-    fn strings(&self) -> Vec<String> {
-        vec![self.name.get()]
+impl Season {
+    /// The season in the given year, as the timeline counts seasons
+    pub fn during(&self, year: i32, seasons: &SeasonModel) -> Option<Interval> {
+        let (month, day) = seasons.starts(self);
+        let (next_month, next_day) = seasons.starts(&self.next());
+        // Den årstid som sträcker sig över nyår hör till det år konventionen säger
+        let over_new_year = (next_month, next_day) < (month, day);
+        let year = match seasons.winter_year {
+            WinterYear::Starting => year,
+            WinterYear::Ending if over_new_year => year - 1,
+            WinterYear::Ending => year,
+        };
+        let first = NaiveDate::from_ymd_opt(year, month, day)?;
+        let next = NaiveDate::from_ymd_opt(year + over_new_year as i32, next_month, next_day)?;
+        Some(Interval::new(first, next.pred_opt()?))
     }
-}
 
-// TRAITS
+    /// The latest occurrence of the season that is over before `d`
+    pub fn before(&self, d: NaiveDate, seasons: &SeasonModel) -> Option<Interval> {
+        (d.year() - 2..=d.year() + 1)
+            .rev()
+            .filter_map(|y| self.during(y, seasons))
+            .find(|s| s.latest() < d)
+    }
 
-pub trait LevelUp {
-    fn parent(&self) -> Identifier;
-}
+    /// The first occurrence of the season that begins after `d`
+    pub fn after(&self, d: NaiveDate, seasons: &SeasonModel) -> Option<Interval> {
+        (d.year() - 1..=d.year() + 2)
+            .filter_map(|y| self.during(y, seasons))
+            .find(|s| s.earliest() > d)
+    }
 
-pub trait HasBeginning {
-    fn begins(&self) -> NaiveDate;
-    fn try_begins(&self) -> Option<NaiveDate> {
-        Some(self.begins())
+    fn next(&self) -> Season {
+        match self {
+            Season::Winter => Season::Spring,
+            Season::Spring => Season::Summer,
+            Season::Summer => Season::Fall,
+            Season::Fall => Season::Winter,
+        }
+    }
+
+    fn opposite(&self) -> Season {
+        self.next().next()
     }
 }
 
-pub trait MyName {
-    fn name(&self) -> MaybeSignal<String>;
+/// How a timeline counts its seasons
+#[derive(Default, Debug, Clone, Copy, PartialEq, Hash)]
+pub struct SeasonModel {
+    pub reckoning: Reckoning,
+    pub hemisphere: Hemisphere,
+    pub winter_year: WinterYear,
 }
 
-pub trait Identify {
-    fn identity(&self) -> Identifier;
+impl SeasonModel {
+    /// The month and day the season begins on
+    fn starts(&self, season: &Season) -> (u32, u32) {
+        let season = match self.hemisphere {
+            Hemisphere::Northern => *season,
+            Hemisphere::Southern => season.opposite(),
+        };
+        match (self.reckoning, season) {
+            (Reckoning::Meteorological, Season::Spring) => (3, 1),
+            (Reckoning::Meteorological, Season::Summer) => (6, 1),
+            (Reckoning::Meteorological, Season::Fall) => (9, 1),
+            (Reckoning::Meteorological, Season::Winter) => (12, 1),
+            // Dagjämningar och solstånd flyttar sig en dag hit eller dit mellan åren
+            (Reckoning::Astronomical, Season::Spring) => (3, 20),
+            (Reckoning::Astronomical, Season::Summer) => (6, 21),
+            (Reckoning::Astronomical, Season::Fall) => (9, 22),
+            (Reckoning::Astronomical, Season::Winter) => (12, 21),
+        }
+    }
 }
 
-#[derive(Default, Debug, Clone, Copy)]
-pub enum Ending {
-    At(PointInTime),
-    After, // duration
-    Upon,  // med länk till en PoT
+#[derive(Default, Debug, Clone, Copy, PartialEq, Hash)]
+pub enum Reckoning {
+    /// Whole months, spring from the first of March
     #[default]
-    Undetermined,
+    Meteorological,
+    /// From equinox and solstice, spring from the 20th of March
+    Astronomical,
 }
 
-// TODO work out place
-// pub struct Place {}
-
-// Samma höst (eller annan upplösning) som ...
-
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
-pub enum PointInTime {
-    Approximated(Approximated),
-    // TimeAfter, // duration och PoI
-    ChronologicalAge(i8, Identifier), // ålder och person
-    TimeAgo {
-        at: NaiveDate,
-        time: TimeDelta,
-    },
-    Time(NaiveDateTime),
-    Day(NaiveDate),
-    Month(i32, Month),
-    Season(i32, Season),
-    // TermOf,
-    Year(i32),
-    // Grade,
-    // Stadium,
-    // Period,
+#[derive(Default, Debug, Clone, Copy, PartialEq, Hash)]
+pub enum Hemisphere {
     #[default]
-    Undetermined,
+    Northern,
+    /// Summer around the new year
+    Southern,
 }
 
-impl HasBeginning for PointInTime {
-    fn begins(&self) -> NaiveDate {
-        match self {
-            // PointInTime::Approximated(_) => todo!(),
-            // PointInTime::ChronologicalAge(_, _) => todo!(),
-            PointInTime::Time(r) => r.date(),
-            PointInTime::Day(d) => d.clone(),
-            PointInTime::Month(y, m) => NaiveDate::from_ymd_opt(*y, *m as u32, 1).unwrap(), // Check this later
-            PointInTime::Season(y, s) => match s {
-                Season::Winter => NaiveDate::from_ymd_opt(*y, 12, 1).unwrap(),
-                Season::Spring => NaiveDate::from_ymd_opt(*y, 3, 1).unwrap(),
-                Season::Summer => NaiveDate::from_ymd_opt(*y, 6, 1).unwrap(),
-                Season::Fall => NaiveDate::from_ymd_opt(*y, 9, 1).unwrap(),
-            },
-            PointInTime::Year(y) => NaiveDate::from_ymd_opt(*y, 1, 1).unwrap(),
-            PointInTime::Undetermined => todo!(),
-            _ => unreachable!(),
-        }
-    }
-
-    fn try_begins(&self) -> Option<NaiveDate> {
-        Some(self.begins())
-    }
+/// Which year the season that runs over the new year belongs to
+#[derive(Default, Debug, Clone, Copy, PartialEq, Hash)]
+pub enum WinterYear {
+    /// The year it starts in, so winter 2014 runs into 2015
+    #[default]
+    Starting,
+    /// The year it ends in, so winter 2014 started in December 2013
+    Ending,
 }
 
-impl From<NaiveDate> for PointInTime {
-    fn from(value: NaiveDate) -> Self {
-        PointInTime::Day(value)
+impl std::fmt::Display for Season {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Season::Winter => "winter",
+            Season::Spring => "spring",
+            Season::Summer => "summer",
+            Season::Fall => "fall",
+        };
+        write!(f, "{name}")
     }
 }
 
-impl From<(i32, u8)> for PointInTime {
-    fn from((y, m): (i32, u8)) -> Self {
-        PointInTime::Month(y, Month::from(m))
-    }
+/// Half of a Swedish school year
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Term {
+    /// Höstterminen
+    Fall,
+    /// Vårterminen
+    Spring,
 }
 
-impl From<i32> for PointInTime {
-    fn from(y: i32) -> Self {
-        PointInTime::Year(y)
+impl Term {
+    /// The term of the school year that starts in the autumn of the given year.
+    /// Schools set their own dates, so each term covers when any of them may
+    /// start and end it.
+    pub fn during(&self, autumn: i32) -> Option<Interval> {
+        match self {
+            Term::Fall => Some(Interval::new(
+                NaiveDate::from_ymd_opt(autumn, 8, 10)?,
+                NaiveDate::from_ymd_opt(autumn, 12, 23)?,
+            )),
+            Term::Spring => Some(Interval::new(
+                NaiveDate::from_ymd_opt(autumn + 1, 1, 7)?,
+                NaiveDate::from_ymd_opt(autumn + 1, 6, 16)?,
+            )),
+        }
     }
 }
 
-impl PointInTime {
-    // fn grade() // and so on
+/// A stage of the Swedish school system
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Stadium {
+    Preschool,
+    PreschoolClass,
+    Lower,
+    Middle,
+    Upper,
+    UpperSecondary,
 }
 
-// TODO work out proper
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
-pub struct Approximated {
-    after: Option<()>,
-    before: Option<()>,
-    during: Option<()>,
-}
+impl Stadium {
+    /// The grades of the stage. Förskolan comes before any of them.
+    pub fn grades(&self) -> Option<RangeInclusive<u8>> {
+        match self {
+            Stadium::Preschool => None,
+            Stadium::PreschoolClass => Some(0..=0),
+            Stadium::Lower => Some(1..=3),
+            Stadium::Middle => Some(4..=6),
+            Stadium::Upper => Some(7..=9),
+            Stadium::UpperSecondary => Some(10..=12),
+        }
+    }
 
-#[derive(Default, Debug, Clone, Copy, PartialEq)]
-pub enum Season {
-    Winter,
-    Spring,
-    #[default]
-    Summer,
-    Fall,
+    /// The stage a grade is part of
+    pub fn of_grade(grade: u8) -> Option<Self> {
+        [
+            Stadium::PreschoolClass,
+            Stadium::Lower,
+            Stadium::Middle,
+            Stadium::Upper,
+            Stadium::UpperSecondary,
+        ]
+        .into_iter()
+        .find(|s| s.grades().is_some_and(|g| g.contains(&grade)))
+    }
 }
 
 #[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub enum Month {
-    January,
+    January = 1,
     February,
     March,
     April,
@@ -242,23 +1850,37 @@ pub enum Month {
     December,
 }
 
-impl From<u8> for Month {
-    fn from(value: u8) -> Self {
-        match value {
-            1 => Month::January,
-            2 => Month::February,
-            3 => Month::March,
-            4 => Month::April,
-            5 => Month::May,
-            6 => Month::June,
-            7 => Month::July,
-            8 => Month::August,
-            9 => Month::September,
-            10 => Month::October,
-            11 => Month::November,
-            12 => Month::December,
-            _ => Month::January,
-        }
+impl Month {
+    const ALL: [Month; 12] = [
+        Month::January,
+        Month::February,
+        Month::March,
+        Month::April,
+        Month::May,
+        Month::June,
+        Month::July,
+        Month::August,
+        Month::September,
+        Month::October,
+        Month::November,
+        Month::December,
+    ];
+
+    /// The month the date is in
+    pub fn of(date: &NaiveDate) -> Month {
+        Month::ALL[date.month0() as usize]
+    }
+}
+
+impl TryFrom<u8> for Month {
+    type Error = TimeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        value
+            .checked_sub(1)
+            .and_then(|i| Month::ALL.get(i as usize))
+            .copied()
+            .ok_or(TimeError::InvalidMonth(value))
     }
 }
 
@@ -273,9 +1895,17 @@ impl Default for Identifier {
     }
 }
 
-impl ToString for Identifier {
-    fn to_string(&self) -> String {
-        self.0.to_string()
+impl std::fmt::Display for Identifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for Identifier {
+    type Err = uuid::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::parse_str(s).map(Identifier)
     }
 }
 
@@ -288,6 +1918,16 @@ pub struct NonSignalPointOfInterest {
     ends: Ending,
     name: String,
     context: String,
+    tentative: bool,
+    recurs: Option<Recurrence>,
+    calibration: Option<Calibration>,
+    sources: Vec<Identifier>,
+    claims: Vec<Claim>,
+    preferred: Option<Identifier>,
+    recorded: Option<NaiveDateTime>,
+    entities: Vec<Identifier>,
+    place: Option<Identifier>,
+    tags: Vec<Tag>,
 }
 
 impl NonSignalPointOfInterest {
@@ -298,6 +1938,94 @@ impl NonSignalPointOfInterest {
             ..Default::default()
         }
     }
+
+    pub fn within(mut self, timeline: Identifier) -> Self {
+        self.timeline = timeline;
+        self
+    }
+
+    pub fn starting(mut self, starts: PointInTime) -> Self {
+        self.starts = starts;
+        self
+    }
+
+    pub fn ending(mut self, ends: Ending) -> Self {
+        self.ends = ends;
+        self
+    }
+
+    pub fn tentative(mut self) -> Self {
+        self.tentative = true;
+        self
+    }
+
+    pub fn recurring(mut self, recurs: Recurrence) -> Self {
+        self.recurs = Some(recurs);
+        self
+    }
+
+    pub fn calibrated(mut self, calibration: Calibration) -> Self {
+        self.calibration = Some(calibration);
+        self
+    }
+
+    pub fn citing(mut self, source: Identifier) -> Self {
+        if !self.sources.contains(&source) {
+            self.sources.push(source);
+        }
+        self
+    }
+
+    pub fn tagged(mut self, tag: Tag) -> Self {
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
+
+    pub fn at(mut self, place: Identifier) -> Self {
+        self.place = Some(place);
+        self
+    }
+
+    pub fn mentioning(mut self, entity: Identifier) -> Self {
+        if !self.entities.contains(&entity) {
+            self.entities.push(entity);
+        }
+        self
+    }
+
+    /// The first claim starts it, until another is preferred. A source that
+    /// already made a claim has it replaced.
+    pub fn claiming(mut self, claim: Claim) -> Self {
+        self.claims.retain(|c| c.source != claim.source);
+        self.claims.push(claim);
+        if self.preferred.is_none_or(|p| p == claim.source) {
+            self.preferred = Some(claim.source);
+            self.starts = claim.starts;
+        }
+        self.citing(claim.source)
+    }
+
+    pub fn preferring(mut self, source: Identifier) -> Self {
+        if let Some(claim) = self.claims.iter().find(|c| c.source == source) {
+            self.preferred = Some(source);
+            self.starts = claim.starts;
+        }
+        self
+    }
+
+    /// When it was first recorded, if earlier than when it becomes a PoI
+    pub fn recorded(mut self, at: NaiveDateTime) -> Self {
+        self.recorded = Some(at);
+        self
+    }
+}
+
+impl Identify for NonSignalPointOfInterest {
+    fn identity(&self) -> Identifier {
+        self.id
+    }
 }
 
 impl From<NonSignalPointOfInterest> for PointOfInterest {
@@ -310,6 +2038,20 @@ impl From<NonSignalPointOfInterest> for PointOfInterest {
             ends: value.ends.into(),
             name: value.name.into(),
             context: value.context.into(),
+            tentative: value.tentative.into(),
+            recurs: value.recurs.into(),
+            calibration: value.calibration.into(),
+            sources: value.sources.into(),
+            claims: value.claims.into(),
+            preferred: value.preferred.into(),
+            history: vec![value.recorded.map_or_else(
+                || Revision::now(value.starts, value.ends),
+                |at| Revision::at(at, value.starts, value.ends),
+            )]
+            .into(),
+            entities: value.entities.into(),
+            place: value.place.into(),
+            tags: value.tags.into(),
         }
     }
 }
@@ -318,6 +2060,7 @@ impl From<NonSignalPointOfInterest> for PointOfInterest {
 pub struct Timeline {
     id: Identifier,
     person: Identifier,
+    seasons: SeasonModel,
 }
 
 impl Timeline {
@@ -327,6 +2070,14 @@ impl Timeline {
             ..Default::default()
         }
     }
+
+    pub fn seasons(&self) -> SeasonModel {
+        self.seasons
+    }
+
+    pub fn set_seasons(&mut self, seasons: SeasonModel) {
+        self.seasons = seasons;
+    }
 }
 
 impl Identify for Timeline {
@@ -345,6 +2096,7 @@ impl LevelUp for Timeline {
 pub struct MainCategory {
     id: Identifier,
     name: RwSignal<String>,
+    sequence: RwSignal<Sequence>,
 }
 
 impl MainCategory {
@@ -352,8 +2104,30 @@ impl MainCategory {
         Self {
             id: Identifier::default(),
             name: RwSignal::new(name),
+            sequence: RwSignal::new(Sequence::default()),
+        }
+    }
+
+    pub fn new_exclusive(name: String) -> Self {
+        Self {
+            sequence: RwSignal::new(Sequence::Exclusive),
+            ..Self::new(name)
         }
     }
+
+    pub fn sequence(&self) -> RwSignal<Sequence> {
+        self.sequence
+    }
+}
+
+/// How the PoIs of a category relate to each other in time
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+pub enum Sequence {
+    /// Any number of PoIs may be going on at once
+    #[default]
+    Overlapping,
+    /// One at a time, like places lived. A new PoI ends the one before it.
+    Exclusive,
 }
 
 impl MyName for MainCategory {
@@ -374,6 +2148,8 @@ pub struct Person {
     id: Identifier,
     name: String,
     origo: PointInTime,
+    /// Years the start of school was put off, negative when brought forward
+    school_start: i8,
 }
 
 impl Person {
@@ -384,6 +2160,24 @@ impl Person {
             ..Default::default()
         }
     }
+
+    pub fn set_origo(&mut self, origo: PointInTime) {
+        self.origo = origo;
+    }
+
+    pub fn school_start(&self) -> i8 {
+        self.school_start
+    }
+
+    pub fn set_school_start(&mut self, years: i8) {
+        self.school_start = years;
+    }
+
+    /// When the person was born. Has to stand on its own, i.e. not be given
+    /// relative to someone's age.
+    pub fn born(&self) -> Result<Interval, TimeError> {
+        self.origo.resolve(&())
+    }
 }
 
 impl Identify for Person {
@@ -393,7 +2187,7 @@ impl Identify for Person {
 }
 
 impl HasBeginning for Person {
-    fn begins(&self) -> NaiveDate {
+    fn begins(&self) -> Result<NaiveDate, TimeError> {
         self.origo.begins()
     }
 }
@@ -404,3 +2198,628 @@ impl MyName for Person {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    /// PoIs to resolve against, without the rest of the timeline
+    struct Known(Vec<PointOfInterest>);
+
+    impl Lookup for Known {
+        fn person(&self, _id: &Identifier) -> Option<Person> {
+            None
+        }
+
+        fn poi(&self, id: &Identifier) -> Option<PointOfInterest> {
+            self.0.iter().find(|p| p.identity() == *id).copied()
+        }
+
+        fn origo(&self) -> Option<Interval> {
+            None
+        }
+    }
+
+    /// Seen from a fixed day, with nothing else to look up
+    struct Today(NaiveDate);
+
+    impl Lookup for Today {
+        fn person(&self, _id: &Identifier) -> Option<Person> {
+            None
+        }
+
+        fn poi(&self, _id: &Identifier) -> Option<PointOfInterest> {
+            None
+        }
+
+        fn origo(&self) -> Option<Interval> {
+            None
+        }
+
+        fn today(&self) -> NaiveDate {
+            self.0
+        }
+    }
+
+    /// Only the person the timeline is about, to place school years by
+    struct Pupil(Person);
+
+    impl Lookup for Pupil {
+        fn person(&self, id: &Identifier) -> Option<Person> {
+            (self.0.identity() == *id).then(|| self.0.clone())
+        }
+
+        fn poi(&self, _id: &Identifier) -> Option<PointOfInterest> {
+            None
+        }
+
+        fn origo(&self) -> Option<Interval> {
+            self.0.born().ok()
+        }
+    }
+
+    /// Born 12 May 2007, starting school `school_start` years late
+    fn pupil(school_start: i8) -> Pupil {
+        let mut person = Person::new("Ada".to_string(), PointInTime::Day(date(2007, 5, 12)));
+        person.set_school_start(school_start);
+        Pupil(person)
+    }
+
+    fn poi(name: &str, starts: PointInTime) -> PointOfInterest {
+        PointOfInterest::new_bare_with_start(
+            Identifier::default(),
+            Identifier::default(),
+            name.to_string(),
+            starts,
+        )
+    }
+
+    #[test]
+    fn resolves_to_the_precision_given() {
+        let day = PointInTime::from(date(2008, 6, 1));
+        assert_eq!(day.resolve(&()), Ok(Interval::day(date(2008, 6, 1))));
+        assert_eq!(
+            PointInTime::from(2008).resolve(&()),
+            Ok(Interval::new(date(2008, 1, 1), date(2008, 12, 31)))
+        );
+        assert_eq!(
+            PointInTime::Undetermined.resolve(&()),
+            Err(TimeError::Undetermined)
+        );
+    }
+
+    #[test]
+    fn resolves_months_by_their_calendar_number() {
+        let january = PointInTime::Month(2009, Month::January);
+        assert_eq!(
+            january.resolve(&()),
+            Ok(Interval::new(date(2009, 1, 1), date(2009, 1, 31)))
+        );
+        let december = PointInTime::Month(2009, Month::December);
+        assert_eq!(
+            december.resolve(&()),
+            Ok(Interval::new(date(2009, 12, 1), date(2009, 12, 31)))
+        );
+    }
+
+    #[test]
+    fn resolves_time_ago_from_when_it_was_said() {
+        let interview = date(2024, 5, 1);
+        let years = PointInTime::ago(Duration::Years(3), interview);
+        let fuzzy = years.resolve(&()).unwrap();
+        assert_eq!(fuzzy, Interval::new(date(2020, 11, 1), date(2021, 11, 1)));
+        assert_eq!(fuzzy.to_string(), "2020-11-01 – 2021-11-01");
+
+        let days = PointInTime::ago(Duration::Days(10), interview);
+        let exact = days.resolve(&()).unwrap();
+        assert_eq!(exact, Interval::day(date(2024, 4, 21)));
+        assert_eq!(exact.to_string(), "2024-04-21");
+    }
+
+    #[test]
+    fn refuses_dates_the_calendar_does_not_have() {
+        assert_eq!(Month::try_from(12), Ok(Month::December));
+        assert_eq!(Month::try_from(13), Err(TimeError::InvalidMonth(13)));
+        assert_eq!(Month::try_from(0), Err(TimeError::InvalidMonth(0)));
+        assert_eq!(
+            PointInTime::month(2009, 13),
+            Err(TimeError::InvalidMonth(13))
+        );
+        assert_eq!(
+            PointInTime::day(2009, 2, 29),
+            Err(TimeError::InvalidDate(2009, 2, 29))
+        );
+        assert_eq!(PointInTime::week(2015, 53), Ok(PointInTime::Week(2015, 53)));
+        assert_eq!(
+            PointInTime::week(2014, 53),
+            Err(TimeError::InvalidWeek(2014, 53))
+        );
+        // Det som inte går att tidsätta ger ett fel i stället för att krascha
+        assert_eq!(
+            PointInTime::Undetermined.begins(),
+            Err(TimeError::Undetermined)
+        );
+        assert_eq!(PointInTime::Week(2014, 53).try_begins(), None);
+    }
+
+    #[test]
+    fn resolves_a_range_only_when_it_ends_after_it_starts() {
+        let range = |from, to| PointInTime::from((from, to)).resolve(&());
+        assert_eq!(
+            range(date(2015, 8, 10), date(2015, 8, 21)),
+            Ok(Interval::new(date(2015, 8, 10), date(2015, 8, 21)))
+        );
+        assert_eq!(
+            range(date(2015, 8, 21), date(2015, 8, 10)),
+            Err(TimeError::ReversedRange)
+        );
+        // Ändarna får överlappa, som "2015 – augusti 2015"
+        assert_eq!(
+            PointInTime::Range(RangeEnd::Year(2015), RangeEnd::Month(2015, Month::August))
+                .resolve(&()),
+            Ok(Interval::new(date(2015, 1, 1), date(2015, 8, 31)))
+        );
+    }
+
+    #[test]
+    fn follows_the_anchor_when_it_moves() {
+        let runtime = leptos::create_runtime();
+        let moved = poi("Räveby", PointInTime::from(date(2009, 3, 1)));
+        let after = Offset::After(Duration::Days(10));
+        let started = poi("Började", PointInTime::relative_to(&moved, after));
+        let known = Known(vec![moved, started]);
+        assert_eq!(
+            started.starts_within(&known),
+            Ok(Interval::day(date(2009, 3, 11)))
+        );
+
+        moved.set_starts(PointInTime::from(date(2010, 5, 1)));
+        assert_eq!(
+            started.starts_within(&known),
+            Ok(Interval::day(date(2010, 5, 11)))
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn reports_anchors_that_refer_back_to_themselves() {
+        let runtime = leptos::create_runtime();
+        let a = poi("A", PointInTime::from(2009));
+        let b = poi("B", PointInTime::relative_to(&a, Offset::SeasonAfter(Season::Summer)));
+        a.set_starts(PointInTime::relative_to(&b, Offset::Before(Duration::Years(1))));
+        let known = Known(vec![a, b]);
+        assert_eq!(a.starts_within(&known), Err(TimeError::Cycle(a.identity())));
+        assert_eq!(b.starts_within(&known), Err(TimeError::Cycle(b.identity())));
+        runtime.dispose();
+    }
+
+    #[test]
+    fn narrows_to_what_satisfies_every_constraint() {
+        let year = |y| Bound::Dates(Interval::new(date(y, 1, 1), date(y, 12, 31)));
+        let between = Approximated::default().after(year(2008)).before(year(2012));
+        assert_eq!(
+            PointInTime::Approximated(between).resolve(&()),
+            Ok(Interval::new(date(2008, 1, 2), date(2012, 12, 30)))
+        );
+        let impossible = Approximated::default().after(year(2012)).before(year(2008));
+        assert_eq!(
+            PointInTime::Approximated(impossible).resolve(&()),
+            Err(TimeError::Contradiction(
+                Constraint::After,
+                Constraint::Before
+            ))
+        );
+    }
+
+    #[test]
+    fn leaves_sides_open_up_to_today() {
+        let after = Approximated::default().after(Bound::Dates(Interval::day(date(2010, 5, 1))));
+        assert_eq!(
+            PointInTime::Approximated(after).resolve(&Today(date(2015, 1, 1))),
+            Ok(Interval::new(date(2010, 5, 2), date(2015, 1, 1)))
+        );
+        // Utan födelsedatum finns inget som begränsar bakåt
+        let before = Approximated::default().before(Bound::Dates(Interval::day(date(2010, 5, 1))));
+        assert_eq!(
+            PointInTime::Approximated(before).resolve(&Today(date(2015, 1, 1))),
+            Err(TimeError::Unbounded)
+        );
+    }
+
+    #[test]
+    fn ends_a_duration_after_the_start() {
+        let runtime = leptos::create_runtime();
+        let stay = poi("Räveby", PointInTime::from(date(2010, 1, 1)));
+        stay.set_ends(Ending::After(Duration::Days(30)));
+        assert_eq!(
+            stay.ends_within(&Known(vec![stay])),
+            Ok(Interval::day(date(2010, 1, 31)))
+        );
+        // Ett antal år räknas som avrundat, ett halvår åt vardera hållet
+        stay.set_ends(Ending::After(Duration::Years(3)));
+        assert_eq!(
+            stay.ends_within(&Known(vec![stay])),
+            Ok(Interval::new(date(2012, 7, 1), date(2013, 7, 1)))
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn ends_upon_the_start_of_another() {
+        let runtime = leptos::create_runtime();
+        let before = poi("Räveby", PointInTime::from(2009));
+        let after = poi("Ingalunda", PointInTime::Season(2014, Season::Summer));
+        before.set_ends(Ending::Upon(after.identity()));
+        assert_eq!(
+            before.ends_within(&Known(vec![before, after])),
+            after.starts_within(&Known(vec![before, after]))
+        );
+        assert_eq!(
+            before.ends_within(&Known(vec![before])),
+            Err(TimeError::UnknownPoI(after.identity()))
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn counts_summer_over_the_new_year_in_the_southern_hemisphere() {
+        let south = SeasonModel {
+            hemisphere: Hemisphere::Southern,
+            ..SeasonModel::default()
+        };
+        assert_eq!(
+            Season::Summer.during(2014, &south),
+            Some(Interval::new(date(2014, 12, 1), date(2015, 2, 28)))
+        );
+        let ending = SeasonModel {
+            winter_year: WinterYear::Ending,
+            ..south
+        };
+        assert_eq!(
+            Season::Summer.during(2014, &ending),
+            Some(Interval::new(date(2013, 12, 1), date(2014, 2, 28)))
+        );
+        // Vintern ligger då mitt i året, hur året än räknas
+        assert_eq!(
+            Season::Winter.during(2014, &ending),
+            Some(Interval::new(date(2014, 6, 1), date(2014, 8, 31)))
+        );
+    }
+
+    #[test]
+    fn counts_winter_by_the_year_it_starts_or_ends() {
+        let starting = SeasonModel::default();
+        assert_eq!(
+            Season::Winter.during(2015, &starting),
+            Some(Interval::new(date(2015, 12, 1), date(2016, 2, 29)))
+        );
+        let ending = SeasonModel {
+            winter_year: WinterYear::Ending,
+            ..starting
+        };
+        assert_eq!(
+            Season::Winter.during(2015, &ending),
+            Some(Interval::new(date(2014, 12, 1), date(2015, 2, 28)))
+        );
+        let astronomical = SeasonModel {
+            reckoning: Reckoning::Astronomical,
+            ..ending
+        };
+        assert_eq!(
+            Season::Winter.during(2015, &astronomical),
+            Some(Interval::new(date(2014, 12, 21), date(2015, 3, 19)))
+        );
+    }
+
+    #[test]
+    fn recurs_every_so_often_from_the_first_time_up_to_the_end() {
+        let days = |r: Recurrence, from, to| {
+            r.occurrences(Interval::new(from, to), SeasonModel::default())
+                .map(|i| i.earliest())
+                .collect::<Vec<_>>()
+        };
+        // Båda ändarna räknas med
+        assert_eq!(
+            days(
+                Recurrence::Every(Duration::Weeks(1)),
+                date(2010, 1, 1),
+                date(2010, 1, 15)
+            ),
+            vec![date(2010, 1, 1), date(2010, 1, 8), date(2010, 1, 15)]
+        );
+        // Månadsvis från den sista glider inte mot den 28:e
+        assert_eq!(
+            days(
+                Recurrence::Every(Duration::Months(1)),
+                date(2010, 1, 31),
+                date(2010, 4, 30)
+            ),
+            vec![
+                date(2010, 1, 31),
+                date(2010, 2, 28),
+                date(2010, 3, 31),
+                date(2010, 4, 30)
+            ]
+        );
+        assert_eq!(
+            days(
+                Recurrence::Every(Duration::Days(0)),
+                date(2010, 1, 1),
+                date(2010, 1, 15)
+            ),
+            vec![date(2010, 1, 1)]
+        );
+    }
+
+    #[test]
+    fn recurs_every_season_within_the_period_only() {
+        let bounds = Interval::new(date(2010, 1, 1), date(2011, 12, 31));
+        let winters = vec![
+            Interval::new(date(2010, 1, 1), date(2010, 2, 28)),
+            Interval::new(date(2010, 12, 1), date(2011, 2, 28)),
+            Interval::new(date(2011, 12, 1), date(2011, 12, 31)),
+        ];
+        let starting = SeasonModel::default();
+        let winter = Recurrence::EverySeason(Season::Winter);
+        assert_eq!(
+            winter.occurrences(bounds, starting).collect::<Vec<_>>(),
+            winters
+        );
+        // Samma vintrar, hur de än räknas
+        let ending = SeasonModel {
+            winter_year: WinterYear::Ending,
+            ..starting
+        };
+        assert_eq!(
+            winter.occurrences(bounds, ending).collect::<Vec<_>>(),
+            winters
+        );
+        let summers = Recurrence::EverySeason(Season::Summer)
+            .occurrences(Interval::new(date(2010, 7, 1), date(2011, 6, 30)), starting);
+        assert_eq!(
+            summers.collect::<Vec<_>>(),
+            vec![
+                Interval::new(date(2010, 7, 1), date(2010, 8, 31)),
+                Interval::new(date(2011, 6, 1), date(2011, 6, 30)),
+            ]
+        );
+    }
+
+    #[test]
+    fn recurs_up_to_today_while_it_goes_on() {
+        let runtime = leptos::create_runtime();
+        let summers = poi("Hos mormor", PointInTime::from(2010));
+        let today = Today(date(2012, 7, 15));
+        assert_eq!(
+            summers.occurrences(&today).unwrap().collect::<Vec<_>>(),
+            vec![summers.starts_within(&today).unwrap()]
+        );
+        summers.set_recurs(Some(Recurrence::EverySeason(Season::Summer)));
+        assert_eq!(
+            summers.occurrences(&today).unwrap().collect::<Vec<_>>(),
+            vec![
+                Interval::new(date(2010, 6, 1), date(2010, 8, 31)),
+                Interval::new(date(2011, 6, 1), date(2011, 8, 31)),
+                Interval::new(date(2012, 6, 1), date(2012, 7, 15)),
+            ]
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn recurs_within_the_period_as_stated_regardless_of_margin() {
+        let runtime = leptos::create_runtime();
+        let summers = poi("Hos mormor", PointInTime::from(2010));
+        summers.set_ends(Ending::At(PointInTime::from(2011)));
+        summers.set_recurs(Some(Recurrence::EverySeason(Season::Summer)));
+        summers.set_calibration(Some(Calibration {
+            margin: Some(Duration::Years(1)),
+            ..Default::default()
+        }));
+        assert_eq!(
+            summers.starts_within(&()),
+            Ok(Interval::new(date(2009, 1, 1), date(2011, 12, 31)))
+        );
+        assert_eq!(
+            summers.occurrences(&()).unwrap().collect::<Vec<_>>(),
+            vec![
+                Interval::new(date(2010, 6, 1), date(2010, 8, 31)),
+                Interval::new(date(2011, 6, 1), date(2011, 8, 31)),
+            ]
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn filters_by_how_certain_the_date_is() {
+        let runtime = leptos::create_runtime();
+        let told = poi("Flytten", PointInTime::from(2010));
+        // Det som inte bedömts visas alltid
+        assert!(told.is_certain_enough(Some(Certainty::Certain)));
+        told.set_calibration(Some(Calibration {
+            date: Certainty::Uncertain,
+            content: Certainty::Certain,
+            ..Default::default()
+        }));
+        assert!(told.is_certain_enough(None));
+        assert!(told.is_certain_enough(Some(Certainty::Guess)));
+        assert!(told.is_certain_enough(Some(Certainty::Uncertain)));
+        assert!(!told.is_certain_enough(Some(Certainty::Likely)));
+        assert_eq!(
+            told.calibration().map(|c| c.weakest()),
+            Some(Certainty::Uncertain)
+        );
+        runtime.dispose();
+    }
+
+    #[test]
+    fn places_grades_from_the_year_school_started() {
+        let on_time = pupil(0);
+        let ada = on_time.0.identity();
+        assert_eq!(
+            PointInTime::Grade(1, ada).resolve(&on_time),
+            Ok(Interval::new(date(2014, 8, 10), date(2015, 6, 16)))
+        );
+        let deferred = Pupil(Person { school_start: 1, ..on_time.0.clone() });
+        assert_eq!(
+            PointInTime::Grade(1, ada).resolve(&deferred),
+            Ok(Interval::new(date(2015, 8, 10), date(2016, 6, 16)))
+        );
+        let early = Pupil(Person { school_start: -1, ..on_time.0.clone() });
+        assert_eq!(
+            PointInTime::Grade(1, ada).resolve(&early),
+            Ok(Interval::new(date(2013, 8, 10), date(2014, 6, 16)))
+        );
+    }
+
+    #[test]
+    fn places_terms_and_stages_after_a_deferred_or_early_start() {
+        let deferred = pupil(1);
+        let ada = deferred.0.identity();
+        assert_eq!(
+            PointInTime::TermOf(Term::Spring, 3, ada).resolve(&deferred),
+            Ok(Interval::new(date(2018, 1, 7), date(2018, 6, 16)))
+        );
+        // Förskolan räcker fram till förskoleklass, ett år längre när den skjuts upp
+        assert_eq!(
+            PointInTime::Stadium(Stadium::Preschool, ada).resolve(&deferred),
+            Ok(Interval::new(date(2008, 5, 12), date(2014, 8, 9)))
+        );
+        let early = Pupil(Person { school_start: -1, ..deferred.0.clone() });
+        assert_eq!(
+            PointInTime::Stadium(Stadium::Middle, ada).resolve(&early),
+            Ok(Interval::new(date(2016, 8, 10), date(2019, 6, 16)))
+        );
+        let stranger = Identifier::default();
+        assert_eq!(
+            PointInTime::Grade(1, stranger).resolve(&early),
+            Err(TimeError::UnknownPerson(stranger))
+        );
+    }
+
+    #[test]
+    fn goes_by_the_preferred_claim_and_keeps_one_per_source() {
+        let runtime = leptos::create_runtime();
+        let walked = poi("Gick", PointInTime::Undetermined);
+        let (mother, journal) = (Identifier::default(), Identifier::default());
+        walked.claim(Claim {
+            source: mother,
+            starts: PointInTime::from(2008),
+        });
+        walked.claim(Claim {
+            source: journal,
+            starts: PointInTime::from(2009),
+        });
+        assert_eq!(walked.preferred(), Some(mother));
+        assert_eq!(walked.starts(), PointInTime::from(2008));
+        assert!(walked.cites(&journal));
+
+        walked.prefer(&journal);
+        assert_eq!(walked.starts(), PointInTime::from(2009));
+        // En källa utan egen uppgift går inte att gå efter
+        walked.prefer(&Identifier::default());
+        assert_eq!(walked.preferred(), Some(journal));
+
+        // Det källan säger senare ersätter det den sa förut
+        let later = PointInTime::Season(2009, Season::Spring);
+        walked.claim(Claim {
+            source: journal,
+            starts: later,
+        });
+        assert_eq!(walked.claims().len(), 2);
+        assert_eq!(walked.starts(), later);
+
+        let built: PointOfInterest =
+            NonSignalPointOfInterest::new(Identifier::default(), "Gick".to_string())
+                .claiming(Claim {
+                    source: mother,
+                    starts: PointInTime::from(2008),
+                })
+                .claiming(Claim {
+                    source: mother,
+                    starts: PointInTime::from(2009),
+                })
+                .into();
+        assert_eq!(
+            built.claims(),
+            vec![Claim {
+                source: mother,
+                starts: PointInTime::from(2009)
+            }]
+        );
+        assert_eq!(built.starts(), PointInTime::from(2009));
+        runtime.dispose();
+    }
+
+    #[test]
+    fn is_disputed_only_by_claims_that_cannot_both_be_right() {
+        let runtime = leptos::create_runtime();
+        let walked = poi("Gick", PointInTime::Undetermined);
+        let known = Known(vec![walked]);
+        let (mother, journal) = (Identifier::default(), Identifier::default());
+        walked.claim(Claim {
+            source: mother,
+            starts: PointInTime::from(2008),
+        });
+        assert!(!walked.is_disputed(&known));
+        // Våren 2008 ryms inom 2008, så båda kan stämma
+        let spring = PointInTime::Season(2008, Season::Spring);
+        walked.claim(Claim {
+            source: journal,
+            starts: spring,
+        });
+        assert!(!walked.is_disputed(&known));
+
+        walked.claim(Claim {
+            source: journal,
+            starts: PointInTime::from(2009),
+        });
+        assert!(walked.is_disputed(&known));
+        runtime.dispose();
+    }
+
+    #[test]
+    fn keeps_what_was_held_at_each_session() {
+        let runtime = leptos::create_runtime();
+        let at = |d: NaiveDate| d.and_hms_opt(10, 0, 0);
+        let poi: PointOfInterest =
+            NonSignalPointOfInterest::new(Identifier::default(), "Gick".to_string())
+                .starting(PointInTime::from(2008))
+                .recorded(at(date(2024, 3, 12)).unwrap())
+                .into();
+        assert!(!poi.is_recorded_by(at(date(2024, 3, 11))));
+        assert!(poi.is_recorded_by(at(date(2024, 3, 12))));
+
+        poi.set_starts(PointInTime::from(2009));
+        assert_eq!(
+            poi.starts_as_of(at(date(2024, 3, 12))),
+            PointInTime::from(2008)
+        );
+        assert_eq!(poi.starts_as_of(None), PointInTime::from(2009));
+        assert_eq!(poi.history().len(), 2);
+        runtime.dispose();
+    }
+
+    #[test]
+    fn tidies_tags_as_typed() {
+        let tag = Tag::new(" Hälsa / Sömn/ ").unwrap();
+        assert_eq!(tag.to_string(), "hälsa/sömn");
+        assert_eq!(tag.levels().collect::<Vec<_>>(), ["hälsa", "sömn"]);
+        assert_eq!(tag.parent(), Tag::new("hälsa"));
+        assert_eq!(Tag::new("hälsa").unwrap().parent(), None);
+        assert_eq!(Tag::new(" / "), None);
+    }
+
+    #[test]
+    fn nests_tags_by_whole_levels() {
+        let tag = |t| Tag::new(t).unwrap();
+        assert!(tag("hälsa/sömn").is_within(&tag("hälsa")));
+        assert!(tag("hälsa").is_within(&tag("hälsa")));
+        assert!(!tag("hälsa").is_within(&tag("hälsa/sömn")));
+        assert!(!tag("hälsovård").is_within(&tag("hälsa")));
+        assert!(!tag("hälsan/sömn").is_within(&tag("hälsa")));
+    }
+}
@@ -0,0 +1,126 @@
+use indexmap::IndexMap;
+use leptos::SignalGet;
+
+use crate::{
+    data::{ByMainCategory, Chronology},
+    format::{Locale, Style},
+    types::{Identifier, Identify, Interval, Lookup, Sequence, TimeError},
+};
+
+/// Something about the timeline that cannot be right
+#[derive(Debug, Clone, PartialEq)]
+pub enum Warning {
+    /// Cannot be placed in time at all
+    Unplaced { poi: Identifier, error: TimeError },
+    /// Starts before the person was born
+    BeforeBirth { poi: Identifier },
+    /// Ends before it starts
+    EndsBeforeStart { poi: Identifier },
+    /// Goes on at the same time as another PoI, in a category where only one
+    /// can go on at a time. Only ends that are given count, see [`check`].
+    Overlaps {
+        poi: Identifier,
+        other: Identifier,
+        category: Identifier,
+    },
+    /// Its sources tell different versions of when it started
+    Disputed { poi: Identifier },
+}
+
+impl Warning {
+    /// The PoI to go to in order to fix it
+    pub fn poi(&self) -> Identifier {
+        match self {
+            Warning::Unplaced { poi, .. }
+            | Warning::BeforeBirth { poi }
+            | Warning::EndsBeforeStart { poi }
+            | Warning::Overlaps { poi, .. }
+            | Warning::Disputed { poi } => *poi,
+        }
+    }
+
+    pub fn describe(&self, style: &Style, lookup: &impl Lookup) -> String {
+        let name = |id: &Identifier| style.name(id, lookup);
+        match (style.locale, self) {
+            (_, Warning::Unplaced { poi, error }) => {
+                format!("{}: {}", name(poi), style.error(error, lookup))
+            }
+            (Locale::Swedish, Warning::BeforeBirth { poi }) => {
+                format!("{} börjar före födseln", name(poi))
+            }
+            (Locale::English, Warning::BeforeBirth { poi }) => {
+                format!("{} starts before birth", name(poi))
+            }
+            (Locale::Swedish, Warning::EndsBeforeStart { poi }) => {
+                format!("{} slutar innan den börjar", name(poi))
+            }
+            (Locale::English, Warning::EndsBeforeStart { poi }) => {
+                format!("{} ends before it starts", name(poi))
+            }
+            (Locale::Swedish, Warning::Overlaps { poi, other, .. }) => {
+                format!("{} överlappar {}", name(poi), name(other))
+            }
+            (Locale::English, Warning::Overlaps { poi, other, .. }) => {
+                format!("{} overlaps {}", name(poi), name(other))
+            }
+            (Locale::Swedish, Warning::Disputed { poi }) => {
+                format!("Uppgifterna om {} går isär", name(poi))
+            }
+            (Locale::English, Warning::Disputed { poi }) => {
+                format!("Accounts of {} disagree", name(poi))
+            }
+        }
+    }
+}
+
+/// Goes through every PoI, warning only where something is certainly wrong
+/// however the uncertain dates turn out.
+pub fn check(cats: &IndexMap<Identifier, ByMainCategory>, chronology: &Chronology) -> Vec<Warning> {
+    let born = chronology.origo();
+    let mut warnings = Vec::new();
+    for cat in cats.values() {
+        let mut placed: Vec<(Identifier, Interval, Option<Interval>)> = Vec::new();
+        for poi in chronology
+            .chronological(cat.pois())
+            .into_iter()
+            .filter_map(|id| chronology.poi(&id))
+        {
+            let id = poi.identity();
+            if poi.is_disputed(chronology) {
+                warnings.push(Warning::Disputed { poi: id });
+            }
+            let starts = match poi.starts_within(chronology) {
+                Ok(starts) => starts,
+                Err(TimeError::Undetermined) => continue,
+                Err(error) => {
+                    warnings.push(Warning::Unplaced { poi: id, error });
+                    continue;
+                }
+            };
+            if born.is_some_and(|b| starts.latest() < b.earliest()) {
+                warnings.push(Warning::BeforeBirth { poi: id });
+            }
+            let ends = poi.ends_within(chronology).ok();
+            if ends.is_some_and(|e| e.latest() < starts.earliest()) {
+                warnings.push(Warning::EndsBeforeStart { poi: id });
+            }
+            placed.push((id, starts, ends));
+        }
+        if cat.sequence().get() == Sequence::Exclusive {
+            // Ordnade efter början, så det räcker att jämföra med de som kom före.
+            // Slut som härleds från nästa PoI (infer_ends) är med flit inte med:
+            // de slutar när nästa börjar och kan därför aldrig säkert överlappa.
+            for (i, (poi, starts, _)) in placed.iter().enumerate() {
+                warnings.extend(placed[..i].iter().filter_map(|(other, _, ends)| {
+                    ends.filter(|e| starts.latest() < e.earliest())
+                        .map(|_| Warning::Overlaps {
+                            poi: *poi,
+                            other: *other,
+                            category: cat.category().identity(),
+                        })
+                }));
+            }
+        }
+    }
+    warnings
+}